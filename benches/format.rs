@@ -0,0 +1,103 @@
+//! Formatting throughput over a large array, against the allocating `Display` and `Serialize`
+//! implementations the stack buffer replaced. serde_json output is measured both as the
+//! default strings and as the bare numbers of `as_json_number`, which the legacy
+//! implementation wrote. Run with `cargo bench --bench format`.
+
+use std::fmt::{Display, Formatter, Result as FmtResult, Write};
+use std::hint::black_box;
+use std::time::Instant;
+
+use perfect_decimal::serde::as_json_number;
+use perfect_decimal::{SafeDecimal, MAX_STR_LEN};
+use serde::ser::Error as SerializeError;
+use serde::{Serialize, Serializer};
+
+const LEN: usize = 100_000;
+const ROUNDS: u32 = 20;
+
+/// The previous implementations: `format!` with trimming, and a `serde_json::Number` parsed
+/// back from `to_string`.
+struct Legacy(SafeDecimal);
+
+impl Display for Legacy {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    let integral = self.0.integral();
+    let fractional = self.0.fractional();
+    if fractional == 0 {
+      write!(f, "{}", integral)
+    } else {
+      let mut frac_str = format!("{:0width$}", fractional, width = SafeDecimal::DECIMALS as usize);
+      frac_str = frac_str.trim_end_matches('0').to_string();
+      write!(f, "{}.{}", integral, frac_str)
+    }
+  }
+}
+
+impl Serialize for Legacy {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where S: Serializer {
+    self.to_string().parse::<serde_json::Number>().map_err(S::Error::custom)?.serialize(serializer)
+  }
+}
+
+/// A value serialized through `as_json_number`, as a field using it would be.
+struct JsonNumber(SafeDecimal);
+
+impl Serialize for JsonNumber {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where S: Serializer {
+    as_json_number::serialize(&self.0, serializer)
+  }
+}
+
+fn bench(name: &str, mut run: impl FnMut()) {
+  run();
+  let start = Instant::now();
+  for _ in 0..ROUNDS {
+    run();
+  }
+  let per_value = start.elapsed() / (ROUNDS * LEN as u32);
+  println!("{name:<20} {per_value:>10.1?} per value");
+}
+
+fn main() {
+  let values: Vec<SafeDecimal> = (0..LEN as u64)
+    .map(|i| SafeDecimal::from_units(i * 7_919_993_773 % SafeDecimal::MAX.to_units()))
+    .collect();
+  let legacy: Vec<Legacy> = values.iter().copied().map(Legacy).collect();
+  let numbers: Vec<JsonNumber> = values.iter().copied().map(JsonNumber).collect();
+  assert_eq!(serde_json::to_string(&numbers).unwrap(), serde_json::to_string(&legacy).unwrap());
+
+  let mut text = String::with_capacity(LEN * MAX_STR_LEN);
+  bench("display (legacy)", || {
+    text.clear();
+    legacy.iter().for_each(|value| write!(text, "{value}").unwrap());
+    black_box(&text);
+  });
+  bench("display", || {
+    text.clear();
+    values.iter().for_each(|value| write!(text, "{value}").unwrap());
+    black_box(&text);
+  });
+  bench("to_str_buf", || {
+    let mut buf = [0; MAX_STR_LEN];
+    black_box(values.iter().map(|value| value.to_str_buf(&mut buf).len()).sum::<usize>());
+  });
+
+  let mut json = Vec::with_capacity(LEN * (MAX_STR_LEN + 1));
+  bench("serialize (legacy)", || {
+    json.clear();
+    serde_json::to_writer(&mut json, &legacy).unwrap();
+    black_box(&json);
+  });
+  bench("serialize (string)", || {
+    json.clear();
+    serde_json::to_writer(&mut json, &values).unwrap();
+    black_box(&json);
+  });
+  bench("serialize (number)", || {
+    json.clear();
+    serde_json::to_writer(&mut json, &numbers).unwrap();
+    black_box(&json);
+  });
+}
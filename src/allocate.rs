@@ -0,0 +1,298 @@
+//! Splitting an amount into parts that add up to it exactly.
+
+use std::cmp::Reverse;
+
+use crate::decimal::Decimal;
+use crate::error::{Error, Result};
+use crate::signed::SignedDecimal;
+
+/// Which parts receive the quanta left over after every part got its rounded-down share.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Leftover {
+  /// One quantum each to the parts whose exact share lost the most, earlier parts first on
+  /// ties (the largest remainder method).
+  LargestRemainder,
+  /// One quantum each to the first parts.
+  FirstParts,
+  /// All of them to the last part, as in bank installment plans.
+  LastPart,
+}
+
+/// Splits `total` quanta in proportion to `weights`. Parts with zero weight get nothing.
+fn allocate_quanta(total: u128, weights: &[u64], leftover: Leftover) -> Result<Vec<u128>> {
+  let weight_sum: u128 = weights.iter().map(|&weight| weight as u128).sum();
+  if weight_sum == 0 {
+    return Err(Error::DivisionByZero {});
+  }
+  let mut parts = Vec::with_capacity(weights.len());
+  let mut remainders = Vec::with_capacity(weights.len());
+  for &weight in weights {
+    let exact = total * weight as u128;
+    parts.push(exact / weight_sum);
+    remainders.push(exact % weight_sum);
+  }
+  let left = (total - parts.iter().sum::<u128>()) as usize;
+  let weighted = (0..weights.len()).filter(|&i| weights[i] != 0);
+  match leftover {
+    Leftover::LargestRemainder => {
+      // Remainders add up to `left * weight_sum` and each is below `weight_sum`, so at least
+      // `left` of them are non-zero.
+      let mut order: Vec<usize> = weighted.collect();
+      order.sort_by_key(|&i| (Reverse(remainders[i]), i));
+      order.into_iter().take(left).for_each(|i| parts[i] += 1);
+    }
+    // There are more weighted parts than leftover quanta, which are below one per part.
+    Leftover::FirstParts => weighted.take(left).for_each(|i| parts[i] += 1),
+    Leftover::LastPart => {
+      if let Some(last) = weights.iter().rposition(|&weight| weight != 0) {
+        parts[last] += left as u128;
+      }
+    }
+  }
+  Ok(parts)
+}
+
+/// Splits `total` quanta into `parts` equal shares, as [`allocate_quanta`] does with equal
+/// weights, without building the weights.
+fn split_quanta(
+  total: u128,
+  parts: usize,
+  leftover: Leftover,
+) -> Result<impl ExactSizeIterator<Item=u128>> {
+  if parts == 0 {
+    return Err(Error::DivisionByZero {});
+  }
+  let (share, left) = (total / parts as u128, total % parts as u128);
+  Ok((0..parts).map(move |i| match leftover {
+    // Equal weights leave equal remainders, so the largest go to the first parts too.
+    Leftover::LargestRemainder | Leftover::FirstParts => share + ((i as u128) < left) as u128,
+    Leftover::LastPart if i == parts - 1 => share + left,
+    Leftover::LastPart => share,
+  }))
+}
+
+/// Collects the parts of an even split, failing with [`Error::Overflow`] instead of aborting
+/// when there are too many to allocate.
+fn collect_parts<T>(parts: impl ExactSizeIterator<Item=T>) -> Result<Vec<T>> {
+  let mut collected = Vec::new();
+  collected.try_reserve_exact(parts.len()).map_err(|_| Error::Overflow {})?;
+  collected.extend(parts);
+  Ok(collected)
+}
+
+/// Counts the quanta in `units`, which must be a whole number of a positive `quantum`.
+fn quanta(units: u128, quantum: u128) -> Result<u128> {
+  if quantum == 0 {
+    return Err(Error::DivisionByZero {});
+  }
+  if !units.is_multiple_of(quantum) {
+    return Err(Error::PrecisionLoss {});
+  }
+  Ok(units / quantum)
+}
+
+/// Allocation in multiples of `quantum`, e.g. [`Decimal::MIN_POSITIVE`] for the smallest unit
+/// or `0.01` for cents. The parts always add up to `self` exactly, which must itself be a
+/// multiple of `quantum` or the split fails with [`Error::PrecisionLoss`]. No parts, or only
+/// zero weights, fail with [`Error::DivisionByZero`].
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Decimal<INT_DIGITS, FRAC_DIGITS> {
+  /// Splits into `parts` equal shares, give or take a quantum. More parts than fit in memory
+  /// fail with [`Error::Overflow`].
+  ///
+  /// ```
+  /// use perfect_decimal::{dec, Leftover};
+  ///
+  /// let parts = dec!(100).allocate(3, dec!(0.01), Leftover::FirstParts).unwrap();
+  /// assert_eq!(parts, [dec!(33.34), dec!(33.33), dec!(33.33)]);
+  /// ```
+  pub fn allocate(&self, parts: usize, quantum: Self, leftover: Leftover) -> Result<Vec<Self>> {
+    let quantum = quantum.0 as u128;
+    let parts = split_quanta(quanta(self.0 as u128, quantum)?, parts, leftover)?;
+    collect_parts(parts.map(|part| Self((part * quantum) as u64)))
+  }
+
+  /// Splits in proportion to `weights`, giving one part per weight.
+  pub fn allocate_by(
+    &self,
+    weights: &[u64],
+    quantum: Self,
+    leftover: Leftover,
+  ) -> Result<Vec<Self>> {
+    let quantum = quantum.0 as u128;
+    let parts = allocate_quanta(quanta(self.0 as u128, quantum)?, weights, leftover)?;
+    Ok(parts.into_iter().map(|part| Self((part * quantum) as u64)).collect())
+  }
+}
+
+/// Allocation as for [`Decimal`], with every part taking the sign of `self`. The quantum must
+/// be positive, a negative one fails with [`Error::Negative`].
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> SignedDecimal<INT_DIGITS, FRAC_DIGITS> {
+  pub fn allocate(&self, parts: usize, quantum: Self, leftover: Leftover) -> Result<Vec<Self>> {
+    if quantum.0 < 0 {
+      return Err(Error::Negative {});
+    }
+    let quantum = quantum.0 as u128;
+    let parts = split_quanta(quanta(self.0.unsigned_abs() as u128, quantum)?, parts, leftover)?;
+    let sign = if self.0 < 0 { -1 } else { 1 };
+    collect_parts(parts.map(|part| Self((part * quantum) as i64 * sign)))
+  }
+
+  pub fn allocate_by(
+    &self,
+    weights: &[u64],
+    quantum: Self,
+    leftover: Leftover,
+  ) -> Result<Vec<Self>> {
+    if quantum.0 < 0 {
+      return Err(Error::Negative {});
+    }
+    let quantum = quantum.0 as u128;
+    let parts =
+      allocate_quanta(quanta(self.0.unsigned_abs() as u128, quantum)?, weights, leftover)?;
+    let sign = if self.0 < 0 { -1 } else { 1 };
+    Ok(parts.into_iter().map(|part| Self((part * quantum) as i64 * sign)).collect())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{dec, SafeDecimal, SignedSafeDecimal};
+
+  const CENT: SafeDecimal = dec!(0.01);
+
+  #[test]
+  fn test_allocate() {
+    let unit = SafeDecimal::MIN_POSITIVE;
+    assert_eq!(
+      dec!(1).allocate(3, unit, Leftover::FirstParts).unwrap(),
+      [dec!(0.333334), dec!(0.333333), dec!(0.333333)]
+    );
+    assert_eq!(
+      dec!(100).allocate(3, CENT, Leftover::LastPart).unwrap(),
+      [dec!(33.33), dec!(33.33), dec!(33.34)]
+    );
+    assert_eq!(
+      dec!(0.05).allocate(7, CENT, Leftover::FirstParts).unwrap(),
+      [CENT, CENT, CENT, CENT, CENT, dec!(0), dec!(0)]
+    );
+    assert_eq!(dec!(12).allocate(4, CENT, Leftover::LargestRemainder).unwrap(), [dec!(3); 4]);
+    assert_eq!(
+      SafeDecimal::MAX.allocate(1, unit, Leftover::FirstParts).unwrap(),
+      [SafeDecimal::MAX]
+    );
+
+    assert!(matches!(
+      dec!(1).allocate(0, CENT, Leftover::FirstParts),
+      Err(Error::DivisionByZero {})
+    ));
+    assert!(matches!(
+      dec!(1).allocate(2, SafeDecimal::ZERO, Leftover::FirstParts),
+      Err(Error::DivisionByZero {})
+    ));
+    assert!(matches!(
+      dec!(1.005).allocate(2, CENT, Leftover::FirstParts),
+      Err(Error::PrecisionLoss {})
+    ));
+    assert!(matches!(
+      dec!(1).allocate(usize::MAX, CENT, Leftover::FirstParts),
+      Err(Error::Overflow {})
+    ));
+  }
+
+  #[test]
+  fn test_allocate_by() {
+    let weights = [50, 30, 20];
+    assert_eq!(
+      dec!(0.05).allocate_by(&weights, CENT, Leftover::LargestRemainder).unwrap(),
+      [dec!(0.03), dec!(0.01), dec!(0.01)]
+    );
+    assert_eq!(
+      dec!(0.06).allocate_by(&weights, CENT, Leftover::FirstParts).unwrap(),
+      [dec!(0.04), dec!(0.01), dec!(0.01)]
+    );
+    assert_eq!(
+      dec!(0.05).allocate_by(&weights, CENT, Leftover::LastPart).unwrap(),
+      [dec!(0.02), dec!(0.01), dec!(0.02)]
+    );
+    // Ties go to earlier parts, and zero weights never receive anything.
+    assert_eq!(
+      dec!(0.05).allocate_by(&[0, 1, 1, 0], CENT, Leftover::LargestRemainder).unwrap(),
+      [dec!(0), dec!(0.03), dec!(0.02), dec!(0)]
+    );
+    assert_eq!(
+      dec!(0.05).allocate_by(&[1, 1, 0], CENT, Leftover::LastPart).unwrap(),
+      [dec!(0.02), dec!(0.03), dec!(0)]
+    );
+    assert!(matches!(
+      dec!(1).allocate_by(&[0, 0], CENT, Leftover::FirstParts),
+      Err(Error::DivisionByZero {})
+    ));
+    assert!(matches!(
+      dec!(1).allocate_by(&[], CENT, Leftover::FirstParts),
+      Err(Error::DivisionByZero {})
+    ));
+  }
+
+  #[test]
+  fn test_allocate_sums_exactly() {
+    let weights = [7, 0, 13, 1, 29, u64::MAX];
+    for leftover in [Leftover::LargestRemainder, Leftover::FirstParts, Leftover::LastPart] {
+      for units in (0..=SafeDecimal::MAX.to_units()).step_by(9_876_543_210_987) {
+        let amount = SafeDecimal::from_units(units);
+        let parts = amount.allocate_by(&weights, SafeDecimal::MIN_POSITIVE, leftover).unwrap();
+        assert_eq!(parts.iter().sum::<Option<SafeDecimal>>(), Some(amount));
+        let parts = amount.allocate(7, SafeDecimal::MIN_POSITIVE, leftover).unwrap();
+        assert_eq!(parts.iter().sum::<Option<SafeDecimal>>(), Some(amount));
+      }
+    }
+  }
+
+  #[test]
+  fn test_allocate_signed() {
+    let cent = dec!(0.01, SignedSafeDecimal);
+    assert_eq!(
+      dec!(-100, SignedSafeDecimal).allocate(3, cent, Leftover::FirstParts).unwrap(),
+      [
+        dec!(-33.34, SignedSafeDecimal),
+        dec!(-33.33, SignedSafeDecimal),
+        dec!(-33.33, SignedSafeDecimal)
+      ]
+    );
+    assert_eq!(
+      dec!(0.03, SignedSafeDecimal).allocate_by(&[1, 2], cent, Leftover::LastPart).unwrap(),
+      [cent, dec!(0.02, SignedSafeDecimal)]
+    );
+    assert_eq!(
+      SignedSafeDecimal::MIN
+        .allocate(1, SignedSafeDecimal::MIN_POSITIVE, Leftover::LastPart)
+        .unwrap(),
+      [SignedSafeDecimal::MIN]
+    );
+    assert!(matches!(
+      dec!(1, SignedSafeDecimal).allocate(2, -cent, Leftover::FirstParts),
+      Err(Error::Negative {})
+    ));
+  }
+
+  #[test]
+  fn test_allocate_matches_equal_weights() {
+    for leftover in [Leftover::LargestRemainder, Leftover::FirstParts, Leftover::LastPart] {
+      for parts in 1..=9 {
+        for total in [dec!(0), dec!(0.05), dec!(1), dec!(100), SafeDecimal::MAX] {
+          assert_eq!(
+            total.allocate(parts, SafeDecimal::MIN_POSITIVE, leftover).unwrap(),
+            total.allocate_by(&vec![1; parts], SafeDecimal::MIN_POSITIVE, leftover).unwrap(),
+            "{total} in {parts} {leftover:?}"
+          );
+        }
+        let total = dec!(-0.07, SignedSafeDecimal);
+        let cent = dec!(0.01, SignedSafeDecimal);
+        assert_eq!(
+          total.allocate(parts, cent, leftover).unwrap(),
+          total.allocate_by(&vec![1; parts], cent, leftover).unwrap()
+        );
+      }
+    }
+  }
+}
@@ -0,0 +1,406 @@
+//! Integer-style arithmetic families on top of the `Result` returning operators, and the
+//! operator impls for references.
+
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+use crate::decimal::Decimal;
+use crate::error::{Error, Result};
+use crate::rounding::RoundingMode;
+use crate::signed::SignedDecimal;
+use crate::wide::mul_div;
+
+/// Checked, saturating, wrapping and overflowing arithmetic, in the manner of the primitive
+/// integers. Wrapping is modulo `10^(INT_DIGITS + FRAC_DIGITS)` units, one past the largest
+/// value, and multiplication truncates like [`Mul`] does. Division by zero has no wrapped
+/// result, so division only comes in the checked and saturating forms.
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Decimal<INT_DIGITS, FRAC_DIGITS> {
+  const MODULUS: u128 = Self::MAX_VAL as u128 + 1;
+
+  pub fn checked_add(self, rhs: Self) -> Option<Self> {
+    (self + rhs).ok()
+  }
+
+  pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+    (self - rhs).ok()
+  }
+
+  pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+    (self * rhs).ok()
+  }
+
+  pub fn checked_div(self, rhs: Self) -> Option<Self> {
+    (self / rhs).ok()
+  }
+
+  pub fn checked_rem(self, rhs: Self) -> Option<Self> {
+    (self % rhs).ok()
+  }
+
+  pub fn saturating_add(self, rhs: Self) -> Self {
+    self.checked_add(rhs).unwrap_or(Self::MAX)
+  }
+
+  pub fn saturating_sub(self, rhs: Self) -> Self {
+    self.checked_sub(rhs).unwrap_or(Self::ZERO)
+  }
+
+  pub fn saturating_mul(self, rhs: Self) -> Self {
+    self.checked_mul(rhs).unwrap_or(Self::MAX)
+  }
+
+  /// A zero divisor saturates to the largest value as well.
+  pub fn saturating_div(self, rhs: Self) -> Self {
+    self.checked_div(rhs).unwrap_or(Self::MAX)
+  }
+
+  pub fn wrapping_add(self, rhs: Self) -> Self {
+    self.overflowing_add(rhs).0
+  }
+
+  pub fn wrapping_sub(self, rhs: Self) -> Self {
+    self.overflowing_sub(rhs).0
+  }
+
+  pub fn wrapping_mul(self, rhs: Self) -> Self {
+    self.overflowing_mul(rhs).0
+  }
+
+  pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+    Self::wrap(self.0 as u128 + rhs.0 as u128)
+  }
+
+  pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+    let (wrapped, _) = Self::wrap(self.0 as u128 + Self::MODULUS - rhs.0 as u128);
+    (wrapped, self.0 < rhs.0)
+  }
+
+  pub fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
+    Self::wrap(self.0 as u128 * rhs.0 as u128 / Self::SCALE as u128)
+  }
+
+  fn wrap(units: u128) -> (Self, bool) {
+    (Self((units % Self::MODULUS) as u64), units >= Self::MODULUS)
+  }
+
+  /// Adds in place, leaving `self` untouched on error.
+  pub fn try_add_assign(&mut self, rhs: Self) -> Result {
+    *self = (*self + rhs)?;
+    Ok(())
+  }
+
+  /// Subtracts in place, leaving `self` untouched on error.
+  pub fn try_sub_assign(&mut self, rhs: Self) -> Result {
+    *self = (*self - rhs)?;
+    Ok(())
+  }
+
+  /// Multiplies in place, leaving `self` untouched on error.
+  pub fn try_mul_assign(&mut self, rhs: Self) -> Result {
+    *self = (*self * rhs)?;
+    Ok(())
+  }
+
+  /// Divides in place, leaving `self` untouched on error.
+  pub fn try_div_assign(&mut self, rhs: Self) -> Result {
+    *self = (*self / rhs)?;
+    Ok(())
+  }
+}
+
+/// Checked and saturating arithmetic. The signed range is symmetric rather than two's
+/// complement, so there is no natural wrapping family.
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> SignedDecimal<INT_DIGITS, FRAC_DIGITS> {
+  const MAX_VAL: i128 = Decimal::<INT_DIGITS, FRAC_DIGITS>::MAX_VAL as i128;
+
+  pub fn checked_add(self, rhs: Self) -> Option<Self> {
+    (self + rhs).ok()
+  }
+
+  pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+    (self - rhs).ok()
+  }
+
+  pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+    (self * rhs).ok()
+  }
+
+  pub fn checked_div(self, rhs: Self) -> Option<Self> {
+    (self / rhs).ok()
+  }
+
+  pub fn checked_rem(self, rhs: Self) -> Option<Self> {
+    (self % rhs).ok()
+  }
+
+  pub fn saturating_add(self, rhs: Self) -> Self {
+    Self::saturate(self.0 as i128 + rhs.0 as i128)
+  }
+
+  pub fn saturating_sub(self, rhs: Self) -> Self {
+    Self::saturate(self.0 as i128 - rhs.0 as i128)
+  }
+
+  pub fn saturating_mul(self, rhs: Self) -> Self {
+    let sign = self.0.signum() * rhs.0.signum();
+    self.checked_mul(rhs).unwrap_or(Self::saturate(sign as i128 * Self::MAX_VAL))
+  }
+
+  /// A zero divisor saturates towards the sign of `self`, and `0 / 0` is zero.
+  pub fn saturating_div(self, rhs: Self) -> Self {
+    let sign = match rhs.0 {
+      0 => self.0.signum(),
+      _ => self.0.signum() * rhs.0.signum(),
+    };
+    self.checked_div(rhs).unwrap_or(Self::saturate(sign as i128 * Self::MAX_VAL))
+  }
+
+  fn saturate(units: i128) -> Self {
+    Self(units.clamp(-Self::MAX_VAL, Self::MAX_VAL) as i64)
+  }
+
+  /// Adds in place, leaving `self` untouched on error.
+  pub fn try_add_assign(&mut self, rhs: Self) -> Result {
+    *self = (*self + rhs)?;
+    Ok(())
+  }
+
+  /// Subtracts in place, leaving `self` untouched on error.
+  pub fn try_sub_assign(&mut self, rhs: Self) -> Result {
+    *self = (*self - rhs)?;
+    Ok(())
+  }
+
+  /// Multiplies in place, leaving `self` untouched on error.
+  pub fn try_mul_assign(&mut self, rhs: Self) -> Result {
+    *self = (*self * rhs)?;
+    Ok(())
+  }
+
+  /// Divides in place, leaving `self` untouched on error.
+  pub fn try_div_assign(&mut self, rhs: Self) -> Result {
+    *self = (*self / rhs)?;
+    Ok(())
+  }
+}
+
+/// Implements a binary operator for the reference combinations of a decimal type by
+/// forwarding to the by-value impl.
+macro_rules! forward_ref_binop {
+  ($type:ident, $($trait:ident $method:ident),*) => {$(
+    impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> $trait<&$type<INT_DIGITS, FRAC_DIGITS>>
+      for $type<INT_DIGITS, FRAC_DIGITS>
+    {
+      type Output = Result<Self>;
+
+      fn $method(self, rhs: &Self) -> Self::Output {
+        $trait::$method(self, *rhs)
+      }
+    }
+
+    impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> $trait<$type<INT_DIGITS, FRAC_DIGITS>>
+      for &$type<INT_DIGITS, FRAC_DIGITS>
+    {
+      type Output = Result<$type<INT_DIGITS, FRAC_DIGITS>>;
+
+      fn $method(self, rhs: $type<INT_DIGITS, FRAC_DIGITS>) -> Self::Output {
+        $trait::$method(*self, rhs)
+      }
+    }
+
+    impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> $trait<&$type<INT_DIGITS, FRAC_DIGITS>>
+      for &$type<INT_DIGITS, FRAC_DIGITS>
+    {
+      type Output = Result<$type<INT_DIGITS, FRAC_DIGITS>>;
+
+      fn $method(self, rhs: &$type<INT_DIGITS, FRAC_DIGITS>) -> Self::Output {
+        $trait::$method(*self, *rhs)
+      }
+    }
+  )*};
+}
+
+forward_ref_binop!(Decimal, Add add, Sub sub, Mul mul, Div div, Rem rem);
+forward_ref_binop!(SignedDecimal, Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+/// Scaling by a primitive integer on the raw units, so any count works as long as the result
+/// fits. Division truncates like [`Div`] between decimals, and an integer divided by a
+/// decimal is exact before that truncation.
+macro_rules! integer_ops {
+  ($type:ident, $units:ty, $($int:ty),*) => {$(
+    impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Mul<$int> for $type<INT_DIGITS, FRAC_DIGITS> {
+      type Output = Result<Self>;
+
+      fn mul(self, rhs: $int) -> Self::Output {
+        Self::checked((self.0 as i128 * rhs as i128) as $units)
+      }
+    }
+
+    impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Mul<$type<INT_DIGITS, FRAC_DIGITS>> for $int {
+      type Output = Result<$type<INT_DIGITS, FRAC_DIGITS>>;
+
+      fn mul(self, rhs: $type<INT_DIGITS, FRAC_DIGITS>) -> Self::Output {
+        rhs * self
+      }
+    }
+
+    impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Div<$int> for $type<INT_DIGITS, FRAC_DIGITS> {
+      type Output = Result<Self>;
+
+      fn div(self, rhs: $int) -> Self::Output {
+        if rhs == 0 {
+          return Err(Error::DivisionByZero {});
+        }
+        Self::checked(RoundingMode::Down.divide(self.0 as i128, rhs as i128) as $units)
+      }
+    }
+
+    impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Div<$type<INT_DIGITS, FRAC_DIGITS>> for $int {
+      type Output = Result<$type<INT_DIGITS, FRAC_DIGITS>>;
+
+      fn div(self, rhs: $type<INT_DIGITS, FRAC_DIGITS>) -> Self::Output {
+        if rhs.0 == 0 {
+          return Err(Error::DivisionByZero {});
+        }
+        // `self * SCALE^2` can exceed 128 bits for the most fractional digits.
+        let scale = $type::<INT_DIGITS, FRAC_DIGITS>::SCALE as u128;
+        let divisor = rhs.0 as i128;
+        let negative = divisor < 0;
+        let dividend = self as u128 * scale;
+        let units = mul_div(dividend, scale, divisor.unsigned_abs(), RoundingMode::Down, negative);
+        let units = units
+          .and_then(|units| i128::try_from(units).ok())
+          .ok_or(Error::Overflow {})?;
+        $type::checked((if negative { -units } else { units }) as $units)
+      }
+    }
+  )*};
+}
+
+integer_ops!(Decimal, u128, u32, u64);
+integer_ops!(SignedDecimal, i128, u32, u64);
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Neg
+  for &SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+  type Output = SignedDecimal<INT_DIGITS, FRAC_DIGITS>;
+
+  fn neg(self) -> Self::Output {
+    -*self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use crate::decimal::SafeDecimal;
+  use crate::error::Error;
+  use crate::signed::SignedSafeDecimal;
+
+  fn dec(s: &str) -> SafeDecimal {
+    SafeDecimal::from_str(s).unwrap()
+  }
+
+  fn signed(s: &str) -> SignedSafeDecimal {
+    SignedSafeDecimal::from_str(s).unwrap()
+  }
+
+  #[test]
+  fn test_checked() {
+    assert_eq!(dec("1.5").checked_add(dec("2")), Some(dec("3.5")));
+    assert_eq!(dec("999999999.999999").checked_add(dec("0.000001")), None);
+    assert_eq!(dec("1").checked_sub(dec("2")), None);
+    assert_eq!(dec("100000").checked_mul(dec("100000")), None);
+    assert_eq!(dec("1").checked_div(dec("0")), None);
+    assert_eq!(dec("1").checked_rem(dec("0")), None);
+    assert_eq!(signed("-1").checked_sub(signed("999999999.999999")), None);
+  }
+
+  #[test]
+  fn test_saturating() {
+    let max = dec("999999999.999999");
+    assert_eq!(max.saturating_add(dec("1")), max);
+    assert_eq!(dec("1").saturating_sub(dec("2")), dec("0"));
+    assert_eq!(dec("100000").saturating_mul(dec("100000")), max);
+    assert_eq!(dec("1").saturating_div(dec("0")), max);
+    assert_eq!(dec("6").saturating_div(dec("4")), dec("1.5"));
+
+    let min = signed("-999999999.999999");
+    assert_eq!(min.saturating_sub(signed("1")), min);
+    assert_eq!(min.saturating_add(signed("1")), signed("-999999998.999999"));
+    assert_eq!(signed("-100000").saturating_mul(signed("100000")), min);
+    assert_eq!(signed("-100000").saturating_mul(signed("-100000")), -min);
+    assert_eq!(signed("-1").saturating_div(signed("0")), min);
+    assert_eq!(signed("0").saturating_div(signed("0")), signed("0"));
+  }
+
+  #[test]
+  fn test_wrapping_overflowing() {
+    let max = dec("999999999.999999");
+    assert_eq!(max.overflowing_add(dec("0.000002")), (dec("0.000001"), true));
+    assert_eq!(dec("1").overflowing_add(dec("2")), (dec("3"), false));
+    assert_eq!(dec("0").overflowing_sub(dec("0.000001")), (max, true));
+    assert_eq!(dec("2").overflowing_sub(dec("0.5")), (dec("1.5"), false));
+    assert_eq!(dec("100000").overflowing_mul(dec("10001")), (dec("100000"), true));
+    assert_eq!(dec("1.5").wrapping_mul(dec("2")), dec("3"));
+    assert_eq!(max.wrapping_add(max), dec("999999999.999998"));
+    assert_eq!(dec("1").wrapping_sub(dec("2")), dec("999999999"));
+  }
+
+  #[test]
+  fn test_try_assign() {
+    let mut total = dec("1");
+    total.try_add_assign(dec("2.5")).unwrap();
+    total.try_mul_assign(dec("2")).unwrap();
+    total.try_div_assign(dec("4")).unwrap();
+    total.try_sub_assign(dec("0.75")).unwrap();
+    assert_eq!(total, dec("1"));
+    assert!(matches!(total.try_sub_assign(dec("2")), Err(Error::Overflow {})));
+    assert!(matches!(total.try_div_assign(dec("0")), Err(Error::DivisionByZero {})));
+    assert_eq!(total, dec("1"));
+
+    let mut balance = signed("1");
+    balance.try_sub_assign(signed("2")).unwrap();
+    assert_eq!(balance, signed("-1"));
+  }
+
+  #[test]
+  #[allow(clippy::op_ref)]
+  fn test_reference_operators() {
+    let a = dec("1.5");
+    let b = dec("0.5");
+    assert_eq!((&a + &b).unwrap(), dec("2"));
+    assert_eq!((a - &b).unwrap(), dec("1"));
+    assert_eq!((&a * b).unwrap(), dec("0.75"));
+    assert_eq!((&a / &b).unwrap(), dec("3"));
+    assert_eq!((&a % &b).unwrap(), dec("0"));
+
+    let c = signed("-1.5");
+    assert_eq!((&c + &signed("0.5")).unwrap(), signed("-1"));
+    assert_eq!(-&c, signed("1.5"));
+  }
+
+  #[test]
+  fn test_integer_ops() {
+    assert_eq!((dec("0.000001") * 1_000_000_000u64).unwrap(), dec("1000"));
+    assert_eq!((3u32 * dec("1.25")).unwrap(), dec("3.75"));
+    assert!(matches!(dec("2") * 500_000_000u32, Err(Error::Overflow {})));
+    assert!(matches!(dec("1") * u64::MAX, Err(Error::Overflow {})));
+    assert_eq!((dec("1") / 3u32).unwrap(), dec("0.333333"));
+    assert_eq!((dec("999999999.999999") / u64::MAX).unwrap(), dec("0"));
+    assert!(matches!(dec("1") / 0u64, Err(Error::DivisionByZero {})));
+    assert_eq!((1u32 / dec("3")).unwrap(), dec("0.333333"));
+    assert_eq!((10_000_000_000u64 / dec("20")).unwrap(), dec("500000000"));
+    assert!(matches!(u64::MAX / dec("0.000001"), Err(Error::Overflow {})));
+    assert!(matches!(1u64 / dec("0"), Err(Error::DivisionByZero {})));
+
+    assert_eq!((signed("-0.5") * 3u64).unwrap(), signed("-1.5"));
+    assert_eq!((signed("-1") / 3u32).unwrap(), signed("-0.333333"));
+    assert_eq!((2u32 / signed("-3")).unwrap(), signed("-0.666666"));
+    assert!(matches!(signed("-1") * 1_000_000_000u32, Err(Error::Overflow {})));
+
+    let tiny = crate::Decimal::<0, 15>::MIN_POSITIVE;
+    assert!(matches!(1u32 / tiny, Err(Error::Overflow {})));
+    assert_eq!((tiny * 1_000u32).unwrap(), crate::Decimal::<0, 15>::from_units(1_000));
+  }
+}
@@ -1,128 +1,431 @@
-use std::fmt::{Display, Formatter, Result as FmtResult};
-use std::ops::{Add, Div, Mul, Sub};
+use std::fmt::{Display, Formatter, LowerExp, Result as FmtResult, UpperExp};
+use std::ops::{Add, Div, Mul, Rem, Sub};
 use std::str::FromStr;
 
-use schemars::JsonSchema;
-use serde::{
-  de::Error as DeserializeError, ser::Error as SerializeError, Deserialize, Deserializer,
-  Serialize, Serializer,
-};
-use serde_json::Number as JsonNumber;
-
 use crate::error::{Error, Result};
+use crate::format::{fmt_exp, fmt_units, format_units, Engineering, Notation, MAX_STR_LEN};
+use crate::locale::{parse_format, Formatted, NumberFormat};
+use crate::parse::{parse, ParseOptions};
+use crate::rounding::RoundingMode;
+
+/// Largest integer up to which every value is exactly representable as an f64.
+const F64_EXACT_LIMIT: u64 = 1 << 53;
+
+/// Unsigned fixed-point decimal with `INT_DIGITS` integer digits and `FRAC_DIGITS` fractional
+/// digits, stored as a count of `10^-FRAC_DIGITS` units.
+///
+/// Only configurations whose largest value fits in the 53-bit f64 mantissa are accepted, so
+/// every value survives a round trip through its shortest float representation. Anything
+/// wider fails to compile:
+///
+/// ```compile_fail
+/// let _ = perfect_decimal::Decimal::<12, 4>::new(1, 0);
+/// ```
+///
+/// ```compile_fail
+/// let _ = perfect_decimal::Decimal::<12, 6>::ZERO;
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Decimal<const INT_DIGITS: u32, const FRAC_DIGITS: u32>(pub(crate) u64);
+
+/// 9 integer digits and 6 fractional digits: `0` to `999,999,999.999999`.
+pub type SafeDecimal = Decimal<9, 6>;
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Decimal<INT_DIGITS, FRAC_DIGITS> {
+  const IS_SAFE: bool = match 10u64.checked_pow(INT_DIGITS + FRAC_DIGITS) {
+    Some(limit) => limit - 1 <= F64_EXACT_LIMIT,
+    None => false,
+  };
+  /// Number of fractional digits.
+  pub const DECIMALS: u32 = FRAC_DIGITS;
+  /// Units per whole number, `10^FRAC_DIGITS`.
+  pub const SCALE: u64 = {
+    assert!(Self::IS_SAFE, "decimal configuration exceeds the exact f64 range");
+    10u64.pow(FRAC_DIGITS)
+  };
+  pub(crate) const INT_LIMIT: u64 = 10u64.pow(INT_DIGITS);
+  pub(crate) const MAX_VAL: u64 = Self::INT_LIMIT * Self::SCALE - 1;
+
+  pub const ZERO: Self = {
+    let _ = Self::SCALE;
+    Self(0)
+  };
+  /// Fails to compile for configurations without integer digits.
+  pub const ONE: Self = Self::from_units(Self::SCALE);
+  /// Largest value, all digits nines.
+  pub const MAX: Self = Self(Self::MAX_VAL);
+  /// Smallest positive value, one unit of `10^-FRAC_DIGITS`.
+  pub const MIN_POSITIVE: Self = {
+    let _ = Self::SCALE;
+    Self(1)
+  };
+
+  pub const fn new(integral: u32, fractional: u32) -> Result<Self> {
+    Self::try_from_parts(integral as u64, fractional as u64)
+  }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
-pub struct SafeDecimal(#[schemars(with = "f64")] u64);
+  /// Like [`Decimal::new`], for configurations whose parts may not fit in a `u32`.
+  pub const fn try_from_parts(integral: u64, fractional: u64) -> Result<Self> {
+    if integral >= Self::INT_LIMIT || fractional >= Self::SCALE {
+      return Err(Error::Overflow {});
+    }
+    Ok(Self(integral * Self::SCALE + fractional))
+  }
 
-impl SafeDecimal {
-  const MAX: u32 = 1_000_000_000;
-  const DECIMALS: u32 = 6;
-  const SCALE: u32 = 10u32.pow(Self::DECIMALS);
-  const MAX_VAL: u64 = Self::MAX as u64 * Self::SCALE as u64 - 1;
+  /// Like [`Decimal::try_from_parts`], but panics when out of range, which is a compile error
+  /// in const contexts:
+  ///
+  /// ```compile_fail
+  /// use perfect_decimal::SafeDecimal;
+  ///
+  /// const TOO_BIG: SafeDecimal = SafeDecimal::from_parts(1_000_000_000, 0);
+  /// ```
+  pub const fn from_parts(integral: u64, fractional: u64) -> Self {
+    match Self::try_from_parts(integral, fractional) {
+      Ok(decimal) => decimal,
+      Err(_) => panic!("decimal parts out of range"),
+    }
+  }
 
-  pub fn new(integral: u32, fractional: u32) -> Result<Self> {
-    if integral >= Self::MAX || fractional >= Self::SCALE {
+  /// Creates a decimal from its count of `10^-FRAC_DIGITS` units, failing when above
+  /// [`Decimal::MAX`].
+  pub const fn try_from_units(units: u64) -> Result<Self> {
+    if units > Self::MAX_VAL {
       return Err(Error::Overflow {});
     }
-    Ok(Self(integral as u64 * Self::SCALE as u64 + fractional as u64))
+    Ok(Self(units))
   }
 
-  pub fn integral(&self) -> u32 {
-    (self.0 / Self::SCALE as u64) as u32
+  /// Like [`Decimal::try_from_units`], but panics when out of range, which is a compile
+  /// error in const contexts.
+  pub const fn from_units(units: u64) -> Self {
+    match Self::try_from_units(units) {
+      Ok(decimal) => decimal,
+      Err(_) => panic!("decimal units out of range"),
+    }
   }
 
-  pub fn fractional(&self) -> u32 {
-    (self.0 % Self::SCALE as u64) as u32
+  /// Count of `10^-FRAC_DIGITS` units.
+  pub const fn to_units(self) -> u64 {
+    self.0
+  }
+
+  pub(crate) fn checked(value: u128) -> Result<Self> {
+    if value > Self::MAX_VAL as u128 {
+      return Err(Error::Overflow {});
+    }
+    Ok(Self(value as u64))
+  }
+
+  /// Fails to compile for configurations whose integral part may not fit in a `u32`, which
+  /// use [`Decimal::to_parts`] instead:
+  ///
+  /// ```compile_fail
+  /// let _ = perfect_decimal::Decimal::<12, 3>::MAX.integral();
+  /// ```
+  pub const fn integral(&self) -> u32 {
+    const { assert!(Self::INT_LIMIT - 1 <= u32::MAX as u64, "integral part may not fit in u32") };
+    (self.0 / Self::SCALE) as u32
+  }
+
+  /// Fails to compile for configurations whose fractional part may not fit in a `u32`.
+  pub const fn fractional(&self) -> u32 {
+    const { assert!(Self::SCALE - 1 <= u32::MAX as u64, "fractional part may not fit in u32") };
+    (self.0 % Self::SCALE) as u32
+  }
+
+  /// Integral and fractional parts, for any configuration.
+  pub const fn to_parts(self) -> (u64, u64) {
+    (self.0 / Self::SCALE, self.0 % Self::SCALE)
+  }
+
+  /// Nearest f64. Both operands of the division are exact integers below 2^53 and IEEE 754
+  /// division rounds correctly, so the result is the double nearest to the decimal, whose
+  /// shortest representation is the [`Display`] text of `self`.
+  pub fn to_f64(self) -> f64 {
+    self.0 as f64 / Self::SCALE as f64
+  }
+
+  /// Multiplies, rounding the exact product to `FRAC_DIGITS` digits by `mode`.
+  pub fn mul_rounded(self, rhs: Self, mode: RoundingMode) -> Result<Self> {
+    Self::checked(mode.divide(self.0 as i128 * rhs.0 as i128, Self::SCALE as i128) as u128)
+  }
+
+  /// Divides, rounding the exact quotient to `FRAC_DIGITS` digits by `mode`.
+  pub fn div_rounded(self, rhs: Self, mode: RoundingMode) -> Result<Self> {
+    if rhs.0 == 0 {
+      return Err(Error::DivisionByZero {});
+    }
+    Self::checked(mode.divide(self.0 as i128 * Self::SCALE as i128, rhs.0 as i128) as u128)
+  }
+
+  /// Computes `self * num / den` from the exact product, rounding once by `mode`, so it only
+  /// fails when the result itself is out of range.
+  ///
+  /// ```
+  /// use perfect_decimal::{dec, RoundingMode};
+  ///
+  /// let rent = dec!(1000).mul_div(dec!(17), dec!(30), RoundingMode::HalfEven).unwrap();
+  /// assert_eq!(rent, dec!(566.666667));
+  /// ```
+  pub fn mul_div(self, num: Self, den: Self, mode: RoundingMode) -> Result<Self> {
+    if den.0 == 0 {
+      return Err(Error::DivisionByZero {});
+    }
+    Self::checked(mode.divide(self.0 as i128 * num.0 as i128, den.0 as i128) as u128)
+  }
+
+  /// What percentage `self` is of `whole`, rounded by `mode`. The receiver is the part:
+  ///
+  /// ```
+  /// use perfect_decimal::{dec, RoundingMode};
+  ///
+  /// // 25 is 12.5% of 200.
+  /// assert_eq!(dec!(25).as_percent_of(dec!(200), RoundingMode::HalfEven).unwrap(), dec!(12.5));
+  /// ```
+  pub fn as_percent_of(self, whole: Self, mode: RoundingMode) -> Result<Self> {
+    self.fraction_of(whole, 100, mode)
+  }
+
+  /// What `self` is of `whole` in basis points, hundredths of a percent, rounded by `mode`:
+  ///
+  /// ```
+  /// use perfect_decimal::{dec, RoundingMode};
+  ///
+  /// // 25 is 1250 basis points of 200.
+  /// let bps = dec!(25).as_basis_points_of(dec!(200), RoundingMode::HalfEven).unwrap();
+  /// assert_eq!(bps, dec!(1250));
+  /// ```
+  pub fn as_basis_points_of(self, whole: Self, mode: RoundingMode) -> Result<Self> {
+    self.fraction_of(whole, 10_000, mode)
+  }
+
+  /// Takes `percent` percent of `self`, rounded by `mode`. The receiver is the whole:
+  ///
+  /// ```
+  /// use perfect_decimal::{dec, RoundingMode};
+  ///
+  /// // 12.5% of 200 is 25.
+  /// assert_eq!(dec!(200).apply_percent(dec!(12.5), RoundingMode::HalfEven).unwrap(), dec!(25));
+  /// ```
+  pub fn apply_percent(self, percent: Self, mode: RoundingMode) -> Result<Self> {
+    self.apply_fraction(percent, 100, mode)
+  }
+
+  /// Takes `basis_points` hundredths of a percent of `self`, rounded by `mode`:
+  ///
+  /// ```
+  /// use perfect_decimal::{dec, RoundingMode};
+  ///
+  /// // 1250 basis points of 200 is 25.
+  /// let share = dec!(200).apply_basis_points(dec!(1250), RoundingMode::HalfEven).unwrap();
+  /// assert_eq!(share, dec!(25));
+  /// ```
+  pub fn apply_basis_points(self, basis_points: Self, mode: RoundingMode) -> Result<Self> {
+    self.apply_fraction(basis_points, 10_000, mode)
+  }
+
+  /// `self / whole` in units of one `per`-th.
+  fn fraction_of(self, whole: Self, per: i128, mode: RoundingMode) -> Result<Self> {
+    if whole.0 == 0 {
+      return Err(Error::DivisionByZero {});
+    }
+    let numerator = self.0 as i128 * per * Self::SCALE as i128;
+    Self::checked(mode.divide(numerator, whole.0 as i128) as u128)
+  }
+
+  /// `rate` `per`-ths of `self`.
+  fn apply_fraction(self, rate: Self, per: i128, mode: RoundingMode) -> Result<Self> {
+    let divisor = per * Self::SCALE as i128;
+    Self::checked(mode.divide(self.0 as i128 * rate.0 as i128, divisor) as u128)
+  }
+
+  /// Parses like [`FromStr`], but accepts any number of fractional digits and rounds them
+  /// to `FRAC_DIGITS` by `mode`.
+  pub fn from_str_rounded(s: &str, mode: RoundingMode) -> Result<Self> {
+    Self::parse_with(s, ParseOptions::strict().rounding(Some(mode)))
+  }
+
+  /// Parses decimal text by the same rules as [`FromStr`], usable in const contexts. See
+  /// [`dec!`](crate::dec) for compile-time validated literals.
+  pub const fn parse(s: &str) -> Result<Self> {
+    match parse(s, ParseOptions::strict(), false, FRAC_DIGITS, Self::MAX_VAL) {
+      Ok(parsed) => Ok(Self(parsed.units)),
+      Err(error) => Err(Error::Parse(error)),
+    }
+  }
+
+  /// Parses decimal text accepting the forms enabled in `options`.
+  pub fn parse_with(s: &str, options: ParseOptions) -> Result<Self> {
+    let parsed = parse(s, options, false, FRAC_DIGITS, Self::MAX_VAL)?;
+    Self::checked(parsed.rounded(options) as u128)
+  }
+
+  /// Writes the shortest text of the value, as used by `Display`, into `buf`.
+  ///
+  /// ```
+  /// use perfect_decimal::{dec, MAX_STR_LEN};
+  ///
+  /// let mut buf = [0; MAX_STR_LEN];
+  /// assert_eq!(dec!(12.50).to_str_buf(&mut buf), "12.5");
+  /// ```
+  pub fn to_str_buf<'a>(&self, buf: &'a mut [u8; MAX_STR_LEN]) -> &'a str {
+    format_units(buf, false, self.0, FRAC_DIGITS)
+  }
+
+  /// Displays in engineering notation, see [`Engineering`].
+  pub fn engineering(&self) -> Engineering {
+    Engineering::new(false, self.0, FRAC_DIGITS)
+  }
+
+  /// Formats with digit grouping, a custom decimal mark or fraction digit limits, see
+  /// [`NumberFormat`].
+  pub fn format(&self) -> Formatted {
+    Formatted::new(false, self.0, FRAC_DIGITS)
+  }
+
+  /// Parses text written in `format`, which may leave out the group separators.
+  pub fn parse_format(s: &str, format: NumberFormat) -> Result<Self> {
+    let parsed = parse_format(s, format, false, FRAC_DIGITS, Self::MAX_VAL)?;
+    Ok(Self(parsed.units))
+  }
+
+  /// Converts to another digit configuration, failing with [`Error::PrecisionLoss`] if
+  /// non-zero fractional digits would be dropped and [`Error::Overflow`] if the value does
+  /// not fit.
+  pub fn convert<const TO_INT_DIGITS: u32, const TO_FRAC_DIGITS: u32>(
+    self,
+  ) -> Result<Decimal<TO_INT_DIGITS, TO_FRAC_DIGITS>> {
+    Decimal::checked(rescale(self.0 as u128, FRAC_DIGITS, TO_FRAC_DIGITS)?)
   }
 }
 
-impl Add for SafeDecimal {
-  type Output = Result<SafeDecimal>;
+/// Moves `units` from `from` to `to` fractional digits, refusing to drop non-zero digits.
+pub(crate) fn rescale(units: u128, from: u32, to: u32) -> Result<u128> {
+  if to >= from {
+    return units.checked_mul(10u128.pow(to - from)).ok_or(Error::Overflow {});
+  }
+  let divisor = 10u128.pow(from - to);
+  if !units.is_multiple_of(divisor) {
+    return Err(Error::PrecisionLoss {});
+  }
+  Ok(units / divisor)
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Add for Decimal<INT_DIGITS, FRAC_DIGITS> {
+  type Output = Result<Self>;
 
   fn add(self, rhs: Self) -> Self::Output {
-    Ok(Self(self.0.checked_add(rhs.0).ok_or(Error::Overflow {})?))
+    Self::checked(self.0 as u128 + rhs.0 as u128)
   }
 }
 
-impl Sub for SafeDecimal {
-  type Output = Result<SafeDecimal>;
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Sub for Decimal<INT_DIGITS, FRAC_DIGITS> {
+  type Output = Result<Self>;
 
   fn sub(self, rhs: Self) -> Self::Output {
     Ok(Self(self.0.checked_sub(rhs.0).ok_or(Error::Overflow {})?))
   }
 }
 
-impl Mul for SafeDecimal {
-  type Output = Result<SafeDecimal>;
+/// Truncates the exact product, i.e. [`Decimal::mul_rounded`] with [`RoundingMode::Down`].
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Mul for Decimal<INT_DIGITS, FRAC_DIGITS> {
+  type Output = Result<Self>;
 
   fn mul(self, rhs: Self) -> Self::Output {
-    let res = self.0 as u128 * rhs.0 as u128 / Self::SCALE as u128;
-    if res > Self::MAX_VAL as u128 {
-      return Err(Error::Overflow {});
-    }
-    Ok(Self(res as u64))
+    self.mul_rounded(rhs, RoundingMode::Down)
   }
 }
 
-impl Div for SafeDecimal {
-  type Output = Result<SafeDecimal>;
+/// Truncates the exact quotient, i.e. [`Decimal::div_rounded`] with [`RoundingMode::Down`].
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Div for Decimal<INT_DIGITS, FRAC_DIGITS> {
+  type Output = Result<Self>;
 
   fn div(self, rhs: Self) -> Self::Output {
-    let res = self.0 as u128 * Self::SCALE as u128 / rhs.0 as u128;
-    if res > Self::MAX_VAL as u128 {
-      return Err(Error::Overflow {});
+    self.div_rounded(rhs, RoundingMode::Down)
+  }
+}
+
+/// Remainder of the truncated division, which is always exact.
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Rem for Decimal<INT_DIGITS, FRAC_DIGITS> {
+  type Output = Result<Self>;
+
+  fn rem(self, rhs: Self) -> Self::Output {
+    if rhs.0 == 0 {
+      return Err(Error::DivisionByZero {});
     }
-    Ok(Self(res as u64))
+    Ok(Self(self.0 % rhs.0))
   }
 }
 
-impl FromStr for SafeDecimal {
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> FromStr for Decimal<INT_DIGITS, FRAC_DIGITS> {
   type Err = Error;
 
   fn from_str(s: &str) -> Result<Self> {
-    let mut parts = s.split('.');
-    let integral: u32 = parts.next().ok_or(Error::UnexpectedFormat {})?.parse()?;
-    let maybe_fractional: Option<u32> =
-      parts.next().map(|s| format!("{:0<6}", s.trim_end_matches("0")).parse()).transpose()?;
-    if parts.next().is_some() {
-      return Err(Error::UnexpectedFormat {});
-    }
-    match maybe_fractional {
-      Some(fractional) => Self::new(integral, fractional),
-      None => Self::new(integral, 0),
-    }
+    Self::parse(s)
   }
 }
 
-impl<'de> Deserialize<'de> for SafeDecimal {
-  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-  where D: Deserializer<'de> {
-    String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+/// Writes the shortest exact text, or with a precision such as `{:.2}` exactly that many
+/// fractional digits, rounding half to even as std does for floats. Width, fill, alignment
+/// and the `+` and `0` flags also behave as for floats.
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Display for Decimal<INT_DIGITS, FRAC_DIGITS> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    fmt_units(f, false, self.0, FRAC_DIGITS)
   }
 }
 
-impl Serialize for SafeDecimal {
-  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-  where S: Serializer {
-    self.to_string().parse::<JsonNumber>().map_err(S::Error::custom)?.serialize(serializer)
+/// Exact scientific notation, as in `1.2345e3`, with the same flags and precision rounding
+/// as for floats.
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> LowerExp for Decimal<INT_DIGITS, FRAC_DIGITS> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    fmt_exp(f, false, self.0, FRAC_DIGITS, Notation::Scientific { upper: false })
   }
 }
 
-impl Display for SafeDecimal {
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> UpperExp for Decimal<INT_DIGITS, FRAC_DIGITS> {
   fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-    let integral = self.integral();
-    let fractional = self.fractional();
-    if fractional == 0 {
-      write!(f, "{}", integral)
-    } else {
-      let mut frac_str = format!("{:06}", fractional);
-      frac_str = frac_str.trim_end_matches('0').to_string();
-      write!(f, "{}.{}", integral, frac_str)
+    fmt_exp(f, false, self.0, FRAC_DIGITS, Notation::Scientific { upper: true })
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> From<Decimal<INT_DIGITS, FRAC_DIGITS>> for f64 {
+  fn from(value: Decimal<INT_DIGITS, FRAC_DIGITS>) -> Self {
+    value.to_f64()
+  }
+}
+
+/// Recovers the decimal whose nearest double is `value`, which is unique in the lossless
+/// range. Doubles that are not the nearest double of any decimal with `FRAC_DIGITS` digits
+/// fail with [`Error::PrecisionLoss`].
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> TryFrom<f64>
+  for Decimal<INT_DIGITS, FRAC_DIGITS>
+{
+  type Error = Error;
+
+  fn try_from(value: f64) -> Result<Self> {
+    if !value.is_finite() {
+      return Err(Error::NotFinite {});
     }
+    if value < 0.0 {
+      return Err(Error::Negative {});
+    }
+    // The scaled product is within a fraction of a unit of the decimal it came from.
+    let units = (value * Self::SCALE as f64).round();
+    if units > Self::MAX_VAL as f64 {
+      return Err(Error::Overflow {});
+    }
+    let decimal = Self(units as u64);
+    if decimal.to_f64() != value {
+      return Err(Error::PrecisionLoss {});
+    }
+    Ok(decimal)
   }
 }
 
-impl TryFrom<u32> for SafeDecimal {
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> TryFrom<u32>
+  for Decimal<INT_DIGITS, FRAC_DIGITS>
+{
   type Error = Error;
 
   fn try_from(value: u32) -> Result<Self> {
@@ -130,31 +433,35 @@ impl TryFrom<u32> for SafeDecimal {
   }
 }
 
-impl TryFrom<u64> for SafeDecimal {
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> TryFrom<u64>
+  for Decimal<INT_DIGITS, FRAC_DIGITS>
+{
   type Error = Error;
 
   fn try_from(value: u64) -> Result<Self> {
-    if value > u32::MAX as u64 {
-      return Err(Error::Overflow {});
-    }
-    Self::new(value as u32, 0)
+    Self::try_from_parts(value, 0)
   }
 }
 
-impl TryFrom<u128> for SafeDecimal {
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> TryFrom<u128>
+  for Decimal<INT_DIGITS, FRAC_DIGITS>
+{
   type Error = Error;
 
   fn try_from(value: u128) -> Result<Self> {
-    if value > u32::MAX as u128 {
+    if value > u64::MAX as u128 {
       return Err(Error::Overflow {});
     }
-    Self::new(value as u32, 0)
+    Self::try_from_parts(value as u64, 0)
   }
 }
 
 #[cfg(test)]
 mod tests {
+  use serde::Deserialize;
+
   use super::*;
+  use crate::error::ParseErrorKind;
 
   #[test]
   fn test_new_valid() {
@@ -169,6 +476,35 @@ mod tests {
     assert!(matches!(SafeDecimal::new(0, 1_000_000), Err(Error::Overflow {})));
   }
 
+  #[test]
+  fn test_parts_past_u32() {
+    let integral: u32 = 4_000_000_000;
+    let decimal = Decimal::<12, 3>::new(integral, 5).unwrap();
+    assert_eq!(decimal.to_parts(), (4_000_000_000, 5));
+    let wide = Decimal::<12, 3>::try_from_parts(999_999_999_999, 999).unwrap();
+    assert_eq!(wide, Decimal::<12, 3>::MAX);
+    assert_eq!(wide.to_parts(), (999_999_999_999, 999));
+    assert!(matches!(Decimal::<12, 3>::try_from_parts(0, 1_000), Err(Error::Overflow {})));
+    assert_eq!(Decimal::<7, 8>::MAX.fractional(), 99_999_999);
+  }
+
+  #[test]
+  fn test_constants() {
+    const FEE: SafeDecimal = SafeDecimal::from_parts(2, 500_000);
+    const TICK: SafeDecimal = SafeDecimal::from_units(10_000);
+    assert_eq!(FEE.to_string(), "2.5");
+    assert_eq!(TICK.to_string(), "0.01");
+    assert_eq!(SafeDecimal::ZERO.to_string(), "0");
+    assert_eq!(SafeDecimal::ONE.to_string(), "1");
+    assert_eq!(SafeDecimal::MAX.to_string(), "999999999.999999");
+    assert_eq!(SafeDecimal::MIN_POSITIVE.to_string(), "0.000001");
+    assert_eq!(SafeDecimal::SCALE, 1_000_000);
+    assert_eq!(SafeDecimal::DECIMALS, 6);
+    assert_eq!(Decimal::<12, 3>::MAX.to_string(), "999999999999.999");
+    assert_eq!(SafeDecimal::MAX.to_units(), 999_999_999_999_999);
+    assert!(matches!(SafeDecimal::try_from_units(1_000_000_000_000_000), Err(Error::Overflow {})));
+  }
+
   #[test]
   fn test_add() {
     let a = SafeDecimal::new(1, 500000).unwrap();
@@ -205,6 +541,23 @@ mod tests {
     assert_eq!(result.fractional(), 0);
   }
 
+  #[test]
+  fn test_div_by_zero() {
+    let a = SafeDecimal::new(10, 0).unwrap();
+    let zero = SafeDecimal::new(0, 0).unwrap();
+    assert!(matches!(a / zero, Err(Error::DivisionByZero {})));
+    assert!(matches!(a.div_rounded(zero, RoundingMode::HalfEven), Err(Error::DivisionByZero {})));
+    assert!(matches!(a % zero, Err(Error::DivisionByZero {})));
+  }
+
+  #[test]
+  fn test_rem() {
+    let a = SafeDecimal::from_str("10.5").unwrap();
+    let b = SafeDecimal::from_str("3.2").unwrap();
+    assert_eq!((a % b).unwrap().to_string(), "0.9");
+    assert_eq!((b % a).unwrap(), b);
+  }
+
   #[test]
   fn test_display() {
     let decimal = SafeDecimal::new(123, 456789).unwrap();
@@ -228,6 +581,108 @@ mod tests {
     assert!(SafeDecimal::from_str("123.45.67").is_err());
   }
 
+  #[test]
+  fn test_from_str_too_many_decimals() {
+    assert!(matches!(
+      SafeDecimal::from_str("0.0000001"),
+      Err(Error::Parse(error)) if error.kind() == ParseErrorKind::TooManyFractionalDigits
+    ));
+    assert_eq!(SafeDecimal::from_str("0.1000000").unwrap().to_string(), "0.1");
+  }
+
+  #[test]
+  fn test_from_str_rounded() {
+    let parse = |s, mode| SafeDecimal::from_str_rounded(s, mode).unwrap().to_string();
+    assert_eq!(parse("0.0000005", RoundingMode::HalfEven), "0");
+    assert_eq!(parse("0.0000015", RoundingMode::HalfEven), "0.000002");
+    assert_eq!(parse("0.00000050001", RoundingMode::HalfEven), "0.000001");
+    assert_eq!(parse("0.0000005", RoundingMode::HalfUp), "0.000001");
+    assert_eq!(parse("0.0000005", RoundingMode::HalfDown), "0");
+    assert_eq!(parse("1.23456700000001", RoundingMode::Up), "1.234568");
+    assert_eq!(parse("1.23456700000001", RoundingMode::Ceiling), "1.234568");
+    assert_eq!(parse("1.2345679", RoundingMode::Down), "1.234567");
+    assert_eq!(parse("1.2345679", RoundingMode::Floor), "1.234567");
+    assert_eq!(parse("12.5", RoundingMode::Up), "12.5");
+    assert!(matches!(
+      SafeDecimal::from_str_rounded("999999999.9999995", RoundingMode::HalfUp),
+      Err(Error::Overflow {})
+    ));
+    assert!(SafeDecimal::from_str_rounded("1.2.3", RoundingMode::HalfUp).is_err());
+    assert!(SafeDecimal::from_str_rounded("1.2x", RoundingMode::HalfUp).is_err());
+  }
+
+  #[test]
+  fn test_mul_div_rounded() {
+    let a = SafeDecimal::from_str("0.000003").unwrap();
+    let b = SafeDecimal::from_str("0.5").unwrap();
+    assert_eq!((a * b).unwrap().to_string(), "0.000001");
+    assert_eq!(a.mul_rounded(b, RoundingMode::HalfEven).unwrap().to_string(), "0.000002");
+    assert_eq!(a.mul_rounded(b, RoundingMode::HalfDown).unwrap().to_string(), "0.000001");
+
+    let one = SafeDecimal::from_str("1").unwrap();
+    let three = SafeDecimal::from_str("3").unwrap();
+    assert_eq!((one / three).unwrap().to_string(), "0.333333");
+    assert_eq!(one.div_rounded(three, RoundingMode::Up).unwrap().to_string(), "0.333334");
+    let two = SafeDecimal::from_str("2").unwrap();
+    assert_eq!(two.div_rounded(three, RoundingMode::HalfUp).unwrap().to_string(), "0.666667");
+    assert_eq!(two.div_rounded(three, RoundingMode::Floor).unwrap().to_string(), "0.666666");
+  }
+
+  #[test]
+  fn test_fused_mul_div() {
+    let amount = SafeDecimal::from_str("1000").unwrap();
+    let days = SafeDecimal::from_str("17").unwrap();
+    let period = SafeDecimal::from_str("30").unwrap();
+    assert_eq!(((amount * days).unwrap() / period).unwrap().to_string(), "566.666666");
+    assert_eq!(
+      amount.mul_div(days, period, RoundingMode::HalfEven).unwrap().to_string(),
+      "566.666667"
+    );
+
+    // The intermediate product is far past `MAX`, only the result needs to fit.
+    let max = SafeDecimal::MAX;
+    assert!(matches!(max * max, Err(Error::Overflow {})));
+    assert_eq!(max.mul_div(max, max, RoundingMode::Down).unwrap(), max);
+    assert!(matches!(max.mul_div(period, days, RoundingMode::Down), Err(Error::Overflow {})));
+    assert!(matches!(
+      amount.mul_div(days, SafeDecimal::ZERO, RoundingMode::Down),
+      Err(Error::DivisionByZero {})
+    ));
+  }
+
+  #[test]
+  fn test_percent() {
+    let part = SafeDecimal::from_str("25").unwrap();
+    let whole = SafeDecimal::from_str("200").unwrap();
+    let third = SafeDecimal::from_str("66.666666").unwrap();
+    assert_eq!(part.as_percent_of(whole, RoundingMode::HalfEven).unwrap().to_string(), "12.5");
+    assert_eq!(part.as_basis_points_of(whole, RoundingMode::HalfEven).unwrap().to_string(), "1250");
+    assert_eq!(
+      third.as_percent_of(whole, RoundingMode::HalfEven).unwrap().to_string(),
+      "33.333333"
+    );
+    assert_eq!(part.as_percent_of(third, RoundingMode::HalfEven).unwrap().to_string(), "37.5");
+    assert_eq!(part.as_percent_of(third, RoundingMode::Up).unwrap().to_string(), "37.500001");
+
+    let rate = SafeDecimal::from_str("12.5").unwrap();
+    assert_eq!(whole.apply_percent(rate, RoundingMode::HalfEven).unwrap().to_string(), "25");
+    let odd = SafeDecimal::from_str("0.000013").unwrap();
+    assert_eq!(odd.apply_percent(rate, RoundingMode::HalfEven).unwrap().to_string(), "0.000002");
+    assert_eq!(odd.apply_percent(rate, RoundingMode::Down).unwrap().to_string(), "0.000001");
+    let bps = SafeDecimal::from_str("1250").unwrap();
+    assert_eq!(whole.apply_basis_points(bps, RoundingMode::HalfEven).unwrap().to_string(), "25");
+    assert_eq!(odd.apply_basis_points(bps, RoundingMode::Up).unwrap().to_string(), "0.000002");
+    assert_eq!(odd.apply_basis_points(bps, RoundingMode::Down).unwrap().to_string(), "0.000001");
+    assert!(matches!(
+      SafeDecimal::MAX.apply_percent(whole, RoundingMode::Down),
+      Err(Error::Overflow {})
+    ));
+    assert!(matches!(
+      part.as_percent_of(SafeDecimal::ZERO, RoundingMode::HalfEven),
+      Err(Error::DivisionByZero {})
+    ));
+  }
+
   #[test]
   fn test_from_str_empty_string() {
     assert!(SafeDecimal::from_str("").is_err());
@@ -251,7 +706,87 @@ mod tests {
   fn test_serialize() {
     let decimal = SafeDecimal::from_str("123.45").unwrap();
     let json = serde_json::to_value(decimal).unwrap();
-    assert_eq!(json, serde_json::json!(123.45));
+    assert_eq!(json, serde_json::json!("123.45"));
+    let json = crate::serde::as_json_number::serialize(&decimal, serde_json::value::Serializer);
+    assert_eq!(json.unwrap(), serde_json::json!(123.45));
+  }
+
+  #[test]
+  fn test_serde_json_round_trip() {
+    for input in ["0", "123.45", "0.000001", "999999999.999999"] {
+      let decimal = SafeDecimal::from_str(input).unwrap();
+      let json = serde_json::to_string(&decimal).unwrap();
+      assert_eq!(json, format!("\"{input}\""));
+      assert_eq!(serde_json::from_str::<SafeDecimal>(&json).unwrap(), decimal);
+      assert_eq!(serde_json::from_str::<SafeDecimal>(input).unwrap(), decimal);
+      let value = serde_json::to_value(decimal).unwrap();
+      assert_eq!(serde_json::from_value::<SafeDecimal>(value).unwrap(), decimal);
+    }
+  }
+
+  #[test]
+  fn test_deserialize_number() {
+    let result: SafeDecimal = serde_json::from_str("123.45").unwrap();
+    assert_eq!(result.to_string(), "123.45");
+    assert!(serde_json::from_str::<SafeDecimal>("0.0000001").is_err());
+    assert!(serde_json::from_str::<SafeDecimal>("-1").is_err());
+    assert!(serde_json::from_str::<SafeDecimal>("1000000000").is_err());
+    assert!(serde_json::from_str::<SafeDecimal>("true").is_err());
+  }
+
+  #[test]
+  fn test_deserialize_primitives() {
+    use serde::de::{value::Error as ValueError, IntoDeserializer};
+
+    let from_f64 = |v: f64| -> Result<SafeDecimal, ValueError> {
+      SafeDecimal::deserialize(v.into_deserializer())
+    };
+    assert_eq!(from_f64(0.1).unwrap().to_string(), "0.1");
+    assert_eq!(from_f64(999999999.999999).unwrap().to_string(), "999999999.999999");
+    assert_eq!(from_f64(-0.0).unwrap().to_string(), "0");
+    assert!(from_f64(0.1234567).is_err());
+    assert!(from_f64(f64::NAN).is_err());
+    assert!(from_f64(f64::INFINITY).is_err());
+    assert!(from_f64(1e300).is_err());
+
+    let from_u64: Result<SafeDecimal, ValueError> =
+      SafeDecimal::deserialize(42u64.into_deserializer());
+    assert_eq!(from_u64.unwrap().to_string(), "42");
+    let from_i64: Result<SafeDecimal, ValueError> =
+      SafeDecimal::deserialize((-42i64).into_deserializer());
+    assert!(from_i64.is_err());
+  }
+
+  #[test]
+  fn test_to_f64() {
+    assert_eq!(SafeDecimal::from_str("123.45").unwrap().to_f64(), 123.45);
+    assert_eq!(f64::from(SafeDecimal::from_str("999999999.999999").unwrap()), 999999999.999999);
+    assert_eq!(Decimal::<1, 14>::from_str("0.1").unwrap().to_f64(), 0.1);
+  }
+
+  #[test]
+  fn test_f64_round_trip() {
+    let small = 0..100_000;
+    let spread = (0..=SafeDecimal::MAX_VAL).step_by(4_999_999_937);
+    let top = SafeDecimal::MAX_VAL - 100_000..=SafeDecimal::MAX_VAL;
+    for units in small.chain(spread).chain(top) {
+      let decimal: SafeDecimal = Decimal(units);
+      let float = decimal.to_f64();
+      assert_eq!(float.to_string(), decimal.to_string());
+      assert_eq!(SafeDecimal::try_from(float).unwrap(), decimal);
+    }
+  }
+
+  #[test]
+  fn test_try_from_f64_invalid() {
+    assert!(matches!(SafeDecimal::try_from(f64::NAN), Err(Error::NotFinite {})));
+    assert!(matches!(SafeDecimal::try_from(f64::INFINITY), Err(Error::NotFinite {})));
+    assert!(matches!(SafeDecimal::try_from(-0.5), Err(Error::Negative {})));
+    assert!(matches!(SafeDecimal::try_from(1e9), Err(Error::Overflow {})));
+    assert!(matches!(SafeDecimal::try_from(1e300), Err(Error::Overflow {})));
+    assert!(matches!(SafeDecimal::try_from(0.1234567), Err(Error::PrecisionLoss {})));
+    assert!(matches!(SafeDecimal::try_from(0.1 + 0.2), Err(Error::PrecisionLoss {})));
+    assert_eq!(SafeDecimal::try_from(-0.0).unwrap().to_string(), "0");
   }
 
   #[test]
@@ -273,4 +808,38 @@ mod tests {
     let result: Result<SafeDecimal, Error> = (u128::MAX).try_into();
     assert!(matches!(result, Err(Error::Overflow {})));
   }
+
+  #[test]
+  fn test_add_overflow() {
+    let a = SafeDecimal::from_str("999999999.999999").unwrap();
+    let b = SafeDecimal::from_str("0.000001").unwrap();
+    assert!(matches!(a + b, Err(Error::Overflow {})));
+  }
+
+  #[test]
+  fn test_custom_precision() {
+    let wide = Decimal::<12, 3>::from_str("999999999999.999").unwrap();
+    assert_eq!(wide.to_parts().0, 999_999_999_999);
+    assert_eq!(wide.to_string(), "999999999999.999");
+    assert!(Decimal::<12, 3>::from_str("0.0001").is_err());
+
+    let narrow = Decimal::<7, 8>::from_str("1234567.00000009").unwrap();
+    assert_eq!(narrow.fractional(), 9);
+    assert!(matches!(Decimal::<7, 8>::new(10_000_000, 0), Err(Error::Overflow {})));
+
+    let whole = Decimal::<15, 0>::from_str("123.000").unwrap();
+    assert_eq!(whole.to_string(), "123");
+    assert!(Decimal::<15, 0>::from_str("123.5").is_err());
+  }
+
+  #[test]
+  fn test_convert() {
+    let decimal = SafeDecimal::from_str("1234.5").unwrap();
+    assert_eq!(decimal.convert::<12, 3>().unwrap().to_string(), "1234.5");
+    assert_eq!(decimal.convert::<7, 8>().unwrap().to_string(), "1234.5");
+    assert!(matches!(decimal.convert::<3, 12>(), Err(Error::Overflow {})));
+
+    let precise = SafeDecimal::from_str("1.0005").unwrap();
+    assert!(matches!(precise.convert::<12, 3>(), Err(Error::PrecisionLoss {})));
+  }
 }
@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
 pub type Result<T=(), E=Error> = std::result::Result<T, E>;
 
 #[derive(thiserror::Error, Debug)]
@@ -5,9 +7,127 @@ pub enum Error {
   #[error("Value exceeds max safe decimal")]
   Overflow {},
 
-  #[error("Unexpected decimal format")]
-  UnexpectedFormat {},
+  #[error("Value is negative")]
+  Negative {},
+
+  #[error("Value cannot be represented without loss of precision")]
+  PrecisionLoss {},
+
+  #[error("Value is not a finite number")]
+  NotFinite {},
+
+  #[error("Division by zero")]
+  DivisionByZero {},
 
   #[error(transparent)]
-  ParseInt(#[from] std::num::ParseIntError),
+  Parse(#[from] ParseError),
+}
+
+/// Longest input excerpt kept by a [`ParseError`].
+const SNIPPET_LEN: usize = 16;
+
+/// Why and where decimal text failed to parse.
+#[derive(thiserror::Error, Clone, Copy, Debug, PartialEq, Eq)]
+#[error("{kind} at byte {offset}: {:?}{}", self.snippet(), if self.truncated { "..." } else { "" })]
+pub struct ParseError {
+  kind: ParseErrorKind,
+  offset: usize,
+  snippet: [u8; SNIPPET_LEN],
+  snippet_len: u8,
+  truncated: bool,
+}
+
+impl ParseError {
+  /// Records `kind` at byte `offset` of `input`, keeping an excerpt starting there.
+  pub(crate) const fn new(kind: ParseErrorKind, input: &[u8], offset: usize) -> Self {
+    let mut end =
+      if input.len() - offset > SNIPPET_LEN { offset + SNIPPET_LEN } else { input.len() };
+    // Back off UTF-8 continuation bytes so the excerpt stays valid text.
+    while end > offset && end < input.len() && input[end] & 0xc0 == 0x80 {
+      end -= 1;
+    }
+    let mut snippet = [0; SNIPPET_LEN];
+    let mut i = offset;
+    while i < end {
+      snippet[i - offset] = input[i];
+      i += 1;
+    }
+    Self { kind, offset, snippet, snippet_len: (end - offset) as u8, truncated: end < input.len() }
+  }
+
+  pub fn kind(&self) -> ParseErrorKind {
+    self.kind
+  }
+
+  /// Byte offset of the problem in the input.
+  pub fn offset(&self) -> usize {
+    self.offset
+  }
+
+  /// Up to 16 bytes of the input starting at [`ParseError::offset`].
+  pub fn snippet(&self) -> &str {
+    std::str::from_utf8(&self.snippet[..self.snippet_len as usize]).unwrap_or_default()
+  }
+}
+
+/// An [`Error`] raised at the item with index `index` of an iterator.
+#[derive(thiserror::Error, Debug)]
+#[error("{error} at item {index}")]
+pub struct ItemError {
+  index: usize,
+  #[source]
+  error: Error,
+}
+
+impl ItemError {
+  pub(crate) fn new(index: usize, error: Error) -> Self {
+    Self { index, error }
+  }
+
+  /// Zero-based index of the offending item; for an empty iterator, zero.
+  pub fn index(&self) -> usize {
+    self.index
+  }
+
+  pub fn error(&self) -> &Error {
+    &self.error
+  }
+
+  pub fn into_error(self) -> Error {
+    self.error
+  }
+}
+
+/// Category of a [`ParseError`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+  /// Nothing to parse.
+  Empty,
+  /// A character that is not allowed at its position.
+  InvalidDigit,
+  /// A number or exponent without digits, or a dot the options don't allow bare.
+  MissingDigits,
+  /// Non-zero digits, or zeros the options reject, beyond the type's precision.
+  TooManyFractionalDigits,
+  /// The value is above the type's maximum.
+  IntegralTooLarge,
+  /// A second decimal point.
+  MultipleDots,
+  /// A minus on an unsigned type, or a plus the options don't allow.
+  SignNotAllowed,
+}
+
+impl Display for ParseErrorKind {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.write_str(match self {
+      ParseErrorKind::Empty => "Empty decimal",
+      ParseErrorKind::InvalidDigit => "Invalid digit",
+      ParseErrorKind::MissingDigits => "Missing digits",
+      ParseErrorKind::TooManyFractionalDigits => "Too many fractional digits",
+      ParseErrorKind::IntegralTooLarge => "Integral part too large",
+      ParseErrorKind::MultipleDots => "Multiple decimal points",
+      ParseErrorKind::SignNotAllowed => "Sign not allowed",
+    })
+  }
 }
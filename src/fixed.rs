@@ -0,0 +1,135 @@
+//! A sealed trait over both decimal types and their integer units.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::decimal::Decimal;
+use crate::error::{Error, Result};
+use crate::format::MAX_STR_LEN;
+use crate::parse::ParseOptions;
+use crate::signed::SignedDecimal;
+
+mod sealed {
+  pub trait Sealed {}
+}
+
+/// The decimal types, for code generic over them such as the serde representation modules
+/// and [`SafeRatio::to_decimal`](crate::SafeRatio::to_decimal). Implemented for [`Decimal`]
+/// and [`SignedDecimal`] only.
+pub trait FixedPoint: sealed::Sealed
+where
+  Self: Display+FromStr<Err=Error>+TryFrom<f64, Error=Error>+Serialize+JsonSchema,
+  Self: for<'de> Deserialize<'de>,
+{
+  /// Integer count of `10^-FRAC_DIGITS` units: `u64` for unsigned decimals and `i64` for
+  /// signed ones.
+  type Units: Serialize+for<'de> Deserialize<'de>+Into<i128>+TryFrom<i128>;
+
+  const INT_DIGITS: u32;
+  const FRAC_DIGITS: u32;
+  const SIGNED: bool;
+
+  fn to_units(&self) -> Self::Units;
+
+  fn try_from_units(units: Self::Units) -> Result<Self>;
+
+  fn to_str_buf<'a>(&self, buf: &'a mut [u8; MAX_STR_LEN]) -> &'a str;
+
+  fn to_f64(&self) -> f64;
+
+  fn parse_with(s: &str, options: ParseOptions) -> Result<Self>;
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> sealed::Sealed
+  for Decimal<INT_DIGITS, FRAC_DIGITS>
+{
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> FixedPoint
+  for Decimal<INT_DIGITS, FRAC_DIGITS>
+{
+  type Units = u64;
+
+  const INT_DIGITS: u32 = INT_DIGITS;
+  const FRAC_DIGITS: u32 = FRAC_DIGITS;
+  const SIGNED: bool = false;
+
+  fn to_units(&self) -> u64 {
+    self.0
+  }
+
+  fn try_from_units(units: u64) -> Result<Self> {
+    Self::try_from_units(units)
+  }
+
+  fn to_str_buf<'a>(&self, buf: &'a mut [u8; MAX_STR_LEN]) -> &'a str {
+    self.to_str_buf(buf)
+  }
+
+  fn to_f64(&self) -> f64 {
+    (*self).to_f64()
+  }
+
+  fn parse_with(s: &str, options: ParseOptions) -> Result<Self> {
+    Self::parse_with(s, options)
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> sealed::Sealed
+  for SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> FixedPoint
+  for SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+  type Units = i64;
+
+  const INT_DIGITS: u32 = INT_DIGITS;
+  const FRAC_DIGITS: u32 = FRAC_DIGITS;
+  const SIGNED: bool = true;
+
+  fn to_units(&self) -> i64 {
+    self.0
+  }
+
+  fn try_from_units(units: i64) -> Result<Self> {
+    Self::try_from_units(units)
+  }
+
+  fn to_str_buf<'a>(&self, buf: &'a mut [u8; MAX_STR_LEN]) -> &'a str {
+    self.to_str_buf(buf)
+  }
+
+  fn to_f64(&self) -> f64 {
+    (*self).to_f64()
+  }
+
+  fn parse_with(s: &str, options: ParseOptions) -> Result<Self> {
+    Self::parse_with(s, options)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{dec, SafeDecimal, SignedSafeDecimal};
+
+  fn round_trip<T: FixedPoint+PartialEq+std::fmt::Debug>(value: T) {
+    assert_eq!(T::try_from_units(value.to_units()).unwrap(), value);
+  }
+
+  #[test]
+  fn test_units() {
+    round_trip(dec!(123.45));
+    round_trip(dec!(-123.45, SignedSafeDecimal));
+    assert_eq!(FixedPoint::to_units(&dec!(1.5)), 1_500_000);
+    assert_eq!(FixedPoint::to_units(&dec!(-1.5, SignedDecimal<12, 3>)), -1_500);
+    assert!(<SafeDecimal as FixedPoint>::try_from_units(1_000_000_000_000_000).is_err());
+    assert_eq!(<SignedDecimal<12, 3> as FixedPoint>::FRAC_DIGITS, 3);
+    assert_eq!([SafeDecimal::SIGNED, SignedSafeDecimal::SIGNED], [false, true]);
+  }
+}
@@ -0,0 +1,387 @@
+//! Allocation-free decimal text, shared by `Display`, `Serialize` and `to_str_buf`.
+
+use std::fmt::{Alignment, Display, Formatter, Result as FmtResult, Write};
+
+use crate::rounding::RoundingMode;
+
+/// Buffer length fitting the text of any decimal type: a sign, `0.` and fifteen digits.
+pub const MAX_STR_LEN: usize = 18;
+
+/// A decimal displayed in engineering notation, with an exponent that is a multiple of three
+/// and one to three leading digits, as in `12.5e3`. Formatting flags work as for `{:e}`.
+///
+/// ```
+/// use perfect_decimal::dec;
+///
+/// assert_eq!(dec!(12500).engineering().to_string(), "12.5e3");
+/// assert_eq!(dec!(0.0005).engineering().to_string(), "500e-6");
+/// assert_eq!(format!("{:.1}", dec!(999.96).engineering()), "1.0e3");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Engineering {
+  negative: bool,
+  magnitude: u64,
+  frac_digits: u32,
+}
+
+impl Engineering {
+  pub(crate) fn new(negative: bool, magnitude: u64, frac_digits: u32) -> Self {
+    Self { negative, magnitude, frac_digits }
+  }
+}
+
+impl Display for Engineering {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    fmt_exp(f, self.negative, self.magnitude, self.frac_digits, Notation::Engineering)
+  }
+}
+
+/// Writes the shortest text of `magnitude` units of `10^-frac_digits` to the end of `buf`,
+/// returning the written part.
+pub(crate) fn format_units(
+  buf: &mut [u8; MAX_STR_LEN],
+  negative: bool,
+  magnitude: u64,
+  frac_digits: u32,
+) -> &str {
+  let mut start = write_digits(buf, magnitude, frac_digits, true);
+  if negative {
+    start -= 1;
+    buf[start] = b'-';
+  }
+  std::str::from_utf8(&buf[start..]).expect("decimal text is ASCII")
+}
+
+/// `Display` for `magnitude` units of `10^-frac_digits`, honouring precision, width, fill,
+/// alignment and the `+` and `0` flags the way std does for floats.
+pub(crate) fn fmt_units(
+  f: &mut Formatter<'_>,
+  negative: bool,
+  magnitude: u64,
+  frac_digits: u32,
+) -> FmtResult {
+  let mut buf = [0; MAX_STR_LEN];
+  if f.width().is_none() && f.precision().is_none() && !f.sign_plus() {
+    return f.write_str(format_units(&mut buf, negative, magnitude, frac_digits));
+  }
+  let (start, zeros) = match f.precision() {
+    None => (write_digits(&mut buf, magnitude, frac_digits, true), 0),
+    Some(precision) if precision >= frac_digits as usize => {
+      (write_digits(&mut buf, magnitude, frac_digits, false), precision - frac_digits as usize)
+    }
+    Some(precision) => {
+      let divisor = 10i128.pow(frac_digits - precision as u32);
+      let rounded = RoundingMode::HalfEven.divide(magnitude as i128, divisor) as u64;
+      (write_digits(&mut buf, rounded, precision as u32, false), 0)
+    }
+  };
+  let digits = std::str::from_utf8(&buf[start..]).expect("decimal text is ASCII");
+  write_number(f, negative, digits, frac_digits == 0 && zeros > 0, zeros, None)
+}
+
+/// Scientific notation with one leading digit, or engineering notation with an exponent
+/// that is a multiple of three.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Notation {
+  Scientific { upper: bool },
+  Engineering,
+}
+
+/// `LowerExp` and `UpperExp` for `magnitude` units of `10^-frac_digits`, honouring the same
+/// flags as [`fmt_units`], with the precision counting mantissa fraction digits.
+pub(crate) fn fmt_exp(
+  f: &mut Formatter<'_>,
+  negative: bool,
+  magnitude: u64,
+  frac_digits: u32,
+  notation: Notation,
+) -> FmtResult {
+  let (len, mut exponent) = match magnitude.checked_ilog10() {
+    Some(log) => (log as i32 + 1, log as i32 - frac_digits as i32),
+    None => (1, 0),
+  };
+  let lead = match notation {
+    Notation::Scientific { .. } => 1,
+    Notation::Engineering => exponent.rem_euclid(3) + 1,
+  };
+  exponent -= lead - 1;
+  // The mantissa as units with `mantissa_digits` fractional digits.
+  let (mut mantissa, mut mantissa_digits) = match len - lead {
+    digits if digits >= 0 => (magnitude, digits as u32),
+    digits => (magnitude * 10u64.pow(digits.unsigned_abs()), 0),
+  };
+  let mut zeros = 0;
+  match f.precision() {
+    Some(precision) if precision < mantissa_digits as usize => {
+      let divisor = 10i128.pow(mantissa_digits - precision as u32);
+      mantissa = RoundingMode::HalfEven.divide(mantissa as i128, divisor) as u64;
+      mantissa_digits = precision as u32;
+      // Rounding up into another leading digit only moves the exponent once the mantissa
+      // outgrows its notation.
+      match notation {
+        _ if mantissa < 10u64.pow(lead as u32 + mantissa_digits) => {}
+        Notation::Engineering if lead < 3 => {}
+        Notation::Engineering => {
+          mantissa /= 1000;
+          exponent += 3;
+        }
+        Notation::Scientific { .. } => {
+          mantissa /= 10;
+          exponent += 1;
+        }
+      }
+    }
+    Some(precision) => zeros = precision - mantissa_digits as usize,
+    None => {}
+  }
+  let mut buf = [0; MAX_STR_LEN];
+  let start = write_digits(&mut buf, mantissa, mantissa_digits, f.precision().is_none());
+  let digits = std::str::from_utf8(&buf[start..]).expect("decimal text is ASCII");
+  let marker = if notation == (Notation::Scientific { upper: true }) { 'E' } else { 'e' };
+  write_number(
+    f,
+    negative,
+    digits,
+    mantissa_digits == 0 && zeros > 0,
+    zeros,
+    Some((marker, exponent)),
+  )
+}
+
+/// Writes the sign, `digits`, a point if `point`, `zeros` and an exponent, padded as `f`
+/// asks.
+fn write_number(
+  f: &mut Formatter<'_>,
+  negative: bool,
+  digits: &str,
+  point: bool,
+  zeros: usize,
+  exponent: Option<(char, i32)>,
+) -> FmtResult {
+  let sign = match (negative, f.sign_plus()) {
+    (true, _) => "-",
+    (false, true) => "+",
+    (false, false) => "",
+  };
+  let point = if point { "." } else { "" };
+  let exponent_len = match exponent {
+    Some((_, exponent)) => {
+      2 + (exponent < 0) as usize + exponent.unsigned_abs().checked_ilog10().unwrap_or(0) as usize
+    }
+    None => 0,
+  };
+  let len = sign.len() + digits.len() + point.len() + zeros + exponent_len;
+  let padding = f.width().unwrap_or(0).saturating_sub(len);
+  let body = |f: &mut Formatter<'_>| {
+    f.write_str(digits)?;
+    f.write_str(point)?;
+    write_repeated(f, '0', zeros)?;
+    match exponent {
+      Some((marker, exponent)) => write!(f, "{marker}{exponent}"),
+      None => Ok(()),
+    }
+  };
+  if f.sign_aware_zero_pad() {
+    f.write_str(sign)?;
+    write_repeated(f, '0', padding)?;
+    return body(f);
+  }
+  pad(f, padding, |f| {
+    f.write_str(sign)?;
+    body(f)
+  })
+}
+
+/// Surrounds what `write` writes with `padding` fill characters placed by the alignment.
+pub(crate) fn pad(
+  f: &mut Formatter<'_>,
+  padding: usize,
+  write: impl FnOnce(&mut Formatter<'_>) -> FmtResult,
+) -> FmtResult {
+  let (before, after) = match f.align() {
+    Some(Alignment::Left) => (0, padding),
+    Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+    Some(Alignment::Right) | None => (padding, 0),
+  };
+  let fill = f.fill();
+  write_repeated(f, fill, before)?;
+  write(f)?;
+  write_repeated(f, fill, after)
+}
+
+/// Writes the digits of `magnitude` units of `10^-frac_digits` to the end of `buf`, without
+/// trailing fractional zeros if `trim`, returning where they start.
+fn write_digits(
+  buf: &mut [u8; MAX_STR_LEN],
+  magnitude: u64,
+  frac_digits: u32,
+  trim: bool,
+) -> usize {
+  let scale = 10u64.pow(frac_digits);
+  let mut integral = magnitude / scale;
+  let mut fractional = magnitude % scale;
+  let mut start = MAX_STR_LEN;
+  if frac_digits > 0 && !(trim && fractional == 0) {
+    let mut width = frac_digits;
+    while trim && fractional.is_multiple_of(10) {
+      fractional /= 10;
+      width -= 1;
+    }
+    for _ in 0..width {
+      start -= 1;
+      buf[start] = b'0' + (fractional % 10) as u8;
+      fractional /= 10;
+    }
+    start -= 1;
+    buf[start] = b'.';
+  }
+  loop {
+    start -= 1;
+    buf[start] = b'0' + (integral % 10) as u8;
+    integral /= 10;
+    if integral == 0 {
+      break;
+    }
+  }
+  start
+}
+
+pub(crate) fn write_repeated(f: &mut Formatter<'_>, c: char, count: usize) -> FmtResult {
+  (0..count).try_for_each(|_| f.write_char(c))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{dec, Decimal, ParseOptions, SafeDecimal, SignedSafeDecimal};
+
+  #[test]
+  fn test_format_units() {
+    let mut buf = [0; MAX_STR_LEN];
+    assert_eq!(format_units(&mut buf, false, 0, 6), "0");
+    assert_eq!(format_units(&mut buf, false, 1_500_000, 6), "1.5");
+    assert_eq!(format_units(&mut buf, false, 1, 6), "0.000001");
+    assert_eq!(format_units(&mut buf, true, 120, 2), "-1.2");
+    assert_eq!(format_units(&mut buf, false, 1200, 0), "1200");
+    assert_eq!(format_units(&mut buf, true, 999_999_999_999_999, 15), "-0.999999999999999");
+    assert_eq!(format_units(&mut buf, true, 999_999_999_999_999, 0), "-999999999999999");
+  }
+
+  #[test]
+  fn test_display_precision() {
+    assert_eq!(format!("{:.2}", dec!(1.005)), "1.00");
+    assert_eq!(format!("{:.2}", dec!(1.015)), "1.02");
+    assert_eq!(format!("{:.2}", dec!(1.0051)), "1.01");
+    assert_eq!(format!("{:.0}", dec!(2.5)), "2");
+    assert_eq!(format!("{:.0}", dec!(3.5)), "4");
+    assert_eq!(format!("{:.3}", dec!(1.5)), "1.500");
+    assert_eq!(format!("{:.6}", dec!(7)), "7.000000");
+    assert_eq!(format!("{:.10}", dec!(0.25)), "0.2500000000");
+    assert_eq!(format!("{:.0}", SafeDecimal::MAX), "1000000000");
+    assert_eq!(format!("{:.2}", Decimal::<15, 0>::MAX), "999999999999999.00");
+    assert_eq!(format!("{:.0}", Decimal::<15, 0>::MAX), "999999999999999");
+    assert_eq!(format!("{:.1}", dec!(-0.04, SignedSafeDecimal)), "-0.0");
+    assert_eq!(format!("{:.1}", dec!(-0.05, SignedSafeDecimal)), "-0.0");
+    assert_eq!(format!("{:.1}", dec!(-0.051, SignedSafeDecimal)), "-0.1");
+  }
+
+  #[test]
+  fn test_display_padding() {
+    assert_eq!(format!("{:8}|", dec!(1.5)), "     1.5|");
+    assert_eq!(format!("{:<8}|", dec!(1.5)), "1.5     |");
+    assert_eq!(format!("{:^8}|", dec!(1.5)), "  1.5   |");
+    assert_eq!(format!("{:*>8.2}", dec!(1.5)), "****1.50");
+    assert_eq!(format!("{:€^9}", dec!(-1.5, SignedSafeDecimal)), "€€-1.5€€€");
+    assert_eq!(format!("{:08.2}", dec!(-1.5, SignedSafeDecimal)), "-0001.50");
+    assert_eq!(format!("{:08}", dec!(12.25)), "00012.25");
+    assert_eq!(format!("{:+}", dec!(1.5)), "+1.5");
+    assert_eq!(format!("{:+}", dec!(-1.5, SignedSafeDecimal)), "-1.5");
+    assert_eq!(format!("{:+08.1}", dec!(0)), "+00000.0");
+    assert_eq!(format!("{:2}", dec!(123.45)), "123.45");
+    assert_eq!(format!("{:>12.8}|", dec!(1.5)), "  1.50000000|");
+  }
+
+  #[test]
+  fn test_display_matches_f64() {
+    let values = ["0", "1.5", "-1.5", "123.456", "-0.000001", "999999999.999999"];
+    let specs: [fn(&dyn std::fmt::Display) -> String; 6] = [
+      |v| format!("{v:>12}"),
+      |v| format!("{v:<12}|"),
+      |v| format!("{v:^+12}"),
+      |v| format!("{v:012}"),
+      |v| format!("{v:+}"),
+      |v| format!("{v:_^15}"),
+    ];
+    for value in values {
+      let decimal: SignedSafeDecimal = value.parse().unwrap();
+      let float: f64 = value.parse().unwrap();
+      for spec in specs {
+        assert_eq!(spec(&decimal), spec(&float), "{value}");
+      }
+    }
+  }
+
+  #[test]
+  fn test_exp_matches_f64() {
+    let units = (0..=SafeDecimal::MAX.to_units()).step_by(79_199_937_773).chain([1, 10, 1_000_000]);
+    for units in units {
+      for negative in [false, true] {
+        let decimal =
+          SignedSafeDecimal::from_units(if negative { -(units as i64) } else { units as i64 });
+        let float = decimal.to_f64();
+        assert_eq!(format!("{decimal:e}"), format!("{float:e}"));
+        assert_eq!(format!("{decimal:E}"), format!("{float:E}"));
+        assert_eq!(format!("{decimal:>24e}|"), format!("{float:>24e}|"));
+        assert_eq!(format!("{decimal:+e}"), format!("{float:+e}"));
+        assert_eq!(format!("{decimal:024e}"), format!("{float:024e}"));
+      }
+    }
+  }
+
+  #[test]
+  fn test_exp_precision() {
+    assert_eq!(format!("{:e}", dec!(0)), "0e0");
+    assert_eq!(format!("{:.2e}", dec!(0)), "0.00e0");
+    assert_eq!(format!("{:.2e}", dec!(1234.5)), "1.23e3");
+    assert_eq!(format!("{:.3e}", dec!(1234.5)), "1.234e3");
+    assert_eq!(format!("{:.3e}", dec!(1235.5)), "1.236e3");
+    assert_eq!(format!("{:.1e}", dec!(9.96)), "1.0e1");
+    assert_eq!(format!("{:.0e}", dec!(2.5)), "2e0");
+    assert_eq!(format!("{:.3E}", dec!(1.5)), "1.500E0");
+    assert_eq!(format!("{:.8e}", dec!(0.000001)), "1.00000000e-6");
+    assert_eq!(format!("{:.1e}", Decimal::<15, 0>::MAX), "1.0e15");
+  }
+
+  #[test]
+  fn test_engineering() {
+    let engineering = |value: SafeDecimal| value.engineering().to_string();
+    assert_eq!(engineering(dec!(0)), "0e0");
+    assert_eq!(engineering(dec!(1)), "1e0");
+    assert_eq!(engineering(dec!(12)), "12e0");
+    assert_eq!(engineering(dec!(123.4)), "123.4e0");
+    assert_eq!(engineering(dec!(1234)), "1.234e3");
+    assert_eq!(engineering(dec!(0.5)), "500e-3");
+    assert_eq!(engineering(dec!(0.00001)), "10e-6");
+    assert_eq!(engineering(dec!(0.000001)), "1e-6");
+    assert_eq!(engineering(SafeDecimal::MAX), "999.999999999999e6");
+    assert_eq!(format!("{:.2}", dec!(99.996).engineering()), "100.00e0");
+    assert_eq!(format!("{:.1}", dec!(999.96).engineering()), "1.0e3");
+    assert_eq!(format!("{:.3}", dec!(1500).engineering()), "1.500e3");
+    assert_eq!(format!("{:>10}|", dec!(-1500, SignedSafeDecimal).engineering()), "    -1.5e3|");
+    assert_eq!(format!("{:+09}", dec!(1500).engineering()), "+0001.5e3");
+  }
+
+  #[test]
+  fn test_exp_round_trip() {
+    for units in (0..=SafeDecimal::MAX.to_units()).step_by(79_199_937_773) {
+      let value = SafeDecimal::from_units(units);
+      for text in [format!("{value:e}"), format!("{value:E}"), value.engineering().to_string()] {
+        assert_eq!(
+          SafeDecimal::parse_with(&text, ParseOptions::lenient()).unwrap(),
+          value,
+          "{text}"
+        );
+      }
+    }
+  }
+}
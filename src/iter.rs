@@ -0,0 +1,231 @@
+//! Summing and multiplying iterators of decimals.
+
+use std::iter::{Product, Sum};
+
+use crate::decimal::Decimal;
+use crate::error::{Error, ItemError, Result};
+use crate::fixed::FixedPoint;
+use crate::rounding::RoundingMode;
+use crate::signed::SignedDecimal;
+
+/// Folds with the `Result` returning operators, stopping at the first overflow. The empty sum
+/// is zero and the empty product one; products truncate at each step like
+/// [`Mul`](std::ops::Mul).
+macro_rules! impl_sum_product {
+  ($type:ident) => {
+    impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Sum<$type<INT_DIGITS, FRAC_DIGITS>>
+      for Result<$type<INT_DIGITS, FRAC_DIGITS>>
+    {
+      fn sum<I: Iterator<Item=$type<INT_DIGITS, FRAC_DIGITS>>>(mut iter: I) -> Self {
+        iter.try_fold($type::ZERO, |sum, value| sum + value)
+      }
+    }
+
+    impl<'a, const INT_DIGITS: u32, const FRAC_DIGITS: u32> Sum<&'a $type<INT_DIGITS, FRAC_DIGITS>>
+      for Result<$type<INT_DIGITS, FRAC_DIGITS>>
+    {
+      fn sum<I: Iterator<Item=&'a $type<INT_DIGITS, FRAC_DIGITS>>>(iter: I) -> Self {
+        iter.copied().sum()
+      }
+    }
+
+    impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Sum<$type<INT_DIGITS, FRAC_DIGITS>>
+      for Option<$type<INT_DIGITS, FRAC_DIGITS>>
+    {
+      fn sum<I: Iterator<Item=$type<INT_DIGITS, FRAC_DIGITS>>>(iter: I) -> Self {
+        iter.sum::<Result<_>>().ok()
+      }
+    }
+
+    impl<'a, const INT_DIGITS: u32, const FRAC_DIGITS: u32> Sum<&'a $type<INT_DIGITS, FRAC_DIGITS>>
+      for Option<$type<INT_DIGITS, FRAC_DIGITS>>
+    {
+      fn sum<I: Iterator<Item=&'a $type<INT_DIGITS, FRAC_DIGITS>>>(iter: I) -> Self {
+        iter.copied().sum::<Result<_>>().ok()
+      }
+    }
+
+    impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Product<$type<INT_DIGITS, FRAC_DIGITS>>
+      for Result<$type<INT_DIGITS, FRAC_DIGITS>>
+    {
+      fn product<I: Iterator<Item=$type<INT_DIGITS, FRAC_DIGITS>>>(mut iter: I) -> Self {
+        iter.try_fold($type::ONE, |product, value| product * value)
+      }
+    }
+
+    impl<'a, const INT_DIGITS: u32, const FRAC_DIGITS: u32>
+      Product<&'a $type<INT_DIGITS, FRAC_DIGITS>> for Result<$type<INT_DIGITS, FRAC_DIGITS>>
+    {
+      fn product<I: Iterator<Item=&'a $type<INT_DIGITS, FRAC_DIGITS>>>(iter: I) -> Self {
+        iter.copied().product()
+      }
+    }
+
+    impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Product<$type<INT_DIGITS, FRAC_DIGITS>>
+      for Option<$type<INT_DIGITS, FRAC_DIGITS>>
+    {
+      fn product<I: Iterator<Item=$type<INT_DIGITS, FRAC_DIGITS>>>(iter: I) -> Self {
+        iter.product::<Result<_>>().ok()
+      }
+    }
+
+    impl<'a, const INT_DIGITS: u32, const FRAC_DIGITS: u32>
+      Product<&'a $type<INT_DIGITS, FRAC_DIGITS>> for Option<$type<INT_DIGITS, FRAC_DIGITS>>
+    {
+      fn product<I: Iterator<Item=&'a $type<INT_DIGITS, FRAC_DIGITS>>>(iter: I) -> Self {
+        iter.copied().product::<Result<_>>().ok()
+      }
+    }
+  };
+}
+
+impl_sum_product!(Decimal);
+impl_sum_product!(SignedDecimal);
+
+mod sealed {
+  use crate::decimal::Decimal;
+  use crate::fixed::FixedPoint;
+  use crate::signed::SignedDecimal;
+
+  /// A decimal or a reference to one, as yielded by iterators over decimals.
+  pub trait DecimalItem {
+    type Decimal: FixedPoint;
+
+    fn units(&self) -> i128;
+  }
+
+  macro_rules! impl_decimal_item {
+    ($($type:ident),*) => {$(
+      impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> DecimalItem
+        for $type<INT_DIGITS, FRAC_DIGITS>
+      {
+        type Decimal = Self;
+
+        fn units(&self) -> i128 {
+          self.0.into()
+        }
+      }
+
+      impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> DecimalItem
+        for &$type<INT_DIGITS, FRAC_DIGITS>
+      {
+        type Decimal = $type<INT_DIGITS, FRAC_DIGITS>;
+
+        fn units(&self) -> i128 {
+          self.0.into()
+        }
+      }
+    )*};
+  }
+
+  impl_decimal_item!(Decimal, SignedDecimal);
+}
+
+use sealed::DecimalItem;
+
+/// Aggregates over iterators of decimals, or of references to them, that say which item
+/// failed.
+pub trait DecimalIterator: Iterator+Sized
+where Self::Item: DecimalItem
+{
+  /// Sums the items, failing with the index of the first item whose running total is out of
+  /// range. Unlike [`Sum`], this names the culprit.
+  fn try_sum(self) -> Result<<Self::Item as DecimalItem>::Decimal, ItemError> {
+    let mut sum = 0i128;
+    let mut total = from_units(0).map_err(|error| ItemError::new(0, error))?;
+    for (index, item) in self.enumerate() {
+      sum += item.units();
+      total = from_units(sum).map_err(|error| ItemError::new(index, error))?;
+    }
+    Ok(total)
+  }
+
+  /// Arithmetic mean of the items, rounded by `mode`. The total is kept exactly, so large
+  /// intermediate sums don't overflow; an empty iterator is a
+  /// [`DivisionByZero`](Error::DivisionByZero) at index zero.
+  fn try_mean(self, mode: RoundingMode) -> Result<<Self::Item as DecimalItem>::Decimal, ItemError> {
+    let mut sum = 0i128;
+    let mut count = 0usize;
+    for (index, item) in self.enumerate() {
+      sum = sum.checked_add(item.units()).ok_or(ItemError::new(index, Error::Overflow {}))?;
+      count += 1;
+    }
+    if count == 0 {
+      return Err(ItemError::new(0, Error::DivisionByZero {}));
+    }
+    from_units(mode.divide(sum, count as i128)).map_err(|error| ItemError::new(count - 1, error))
+  }
+}
+
+impl<I: Iterator> DecimalIterator for I where I::Item: DecimalItem {}
+
+fn from_units<T: FixedPoint>(units: i128) -> Result<T> {
+  let units = T::Units::try_from(units).map_err(|_| Error::Overflow {})?;
+  T::try_from_units(units)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{dec, SafeDecimal, SignedSafeDecimal};
+
+  #[test]
+  fn test_sum() {
+    let values = [dec!(1.5), dec!(2.25), dec!(0.000001)];
+    assert_eq!(values.iter().sum::<Result<SafeDecimal>>().unwrap(), dec!(3.750001));
+    assert_eq!(values.into_iter().sum::<Option<SafeDecimal>>(), Some(dec!(3.750001)));
+    assert_eq!(std::iter::empty::<SafeDecimal>().sum::<Option<_>>(), Some(SafeDecimal::ZERO));
+
+    let overflow = [SafeDecimal::MAX, SafeDecimal::MIN_POSITIVE];
+    assert!(matches!(overflow.iter().sum::<Result<SafeDecimal>>(), Err(Error::Overflow {})));
+    assert_eq!(overflow.iter().sum::<Option<SafeDecimal>>(), None);
+
+    let signed = [dec!(-1.5, SignedSafeDecimal), dec!(0.25, SignedSafeDecimal)];
+    assert_eq!(signed.iter().sum::<Option<_>>(), Some(dec!(-1.25, SignedSafeDecimal)));
+  }
+
+  #[test]
+  fn test_product() {
+    let values = [dec!(1.5), dec!(2), dec!(0.333333)];
+    assert_eq!(values.iter().product::<Result<SafeDecimal>>().unwrap(), dec!(0.999999));
+    assert_eq!(std::iter::empty::<SafeDecimal>().product::<Option<_>>(), Some(SafeDecimal::ONE));
+    assert_eq!([dec!(100000), dec!(100000)].iter().product::<Option<SafeDecimal>>(), None);
+
+    let signed = [dec!(-2, SignedSafeDecimal), dec!(-0.5, SignedSafeDecimal)];
+    assert_eq!(signed.into_iter().product::<Result<_>>().unwrap(), SignedSafeDecimal::ONE);
+  }
+
+  #[test]
+  fn test_try_sum() {
+    let values = [dec!(1), dec!(2), dec!(3)];
+    assert_eq!(values.iter().try_sum().unwrap(), dec!(6));
+    assert_eq!(values.into_iter().try_sum().unwrap(), dec!(6));
+    assert_eq!(std::iter::empty::<SafeDecimal>().try_sum().unwrap(), SafeDecimal::ZERO);
+
+    let values = [dec!(1), SafeDecimal::MAX, dec!(2)];
+    let error = values.iter().try_sum().unwrap_err();
+    assert_eq!(error.index(), 1);
+    assert!(matches!(error.error(), Error::Overflow {}));
+    assert_eq!(error.to_string(), "Value exceeds max safe decimal at item 1");
+
+    let max = SignedSafeDecimal::MAX;
+    assert_eq!([max, -max, -max].iter().try_sum().unwrap(), -max);
+    assert_eq!([-max, -SignedSafeDecimal::MIN_POSITIVE].iter().try_sum().unwrap_err().index(), 1);
+  }
+
+  #[test]
+  fn test_try_mean() {
+    let values = [dec!(1), dec!(2), dec!(2)];
+    assert_eq!(values.iter().try_mean(RoundingMode::HalfEven).unwrap(), dec!(1.666667));
+    assert_eq!(values.iter().try_mean(RoundingMode::Down).unwrap(), dec!(1.666666));
+
+    let max = [SafeDecimal::MAX; 3];
+    assert_eq!(max.iter().try_mean(RoundingMode::HalfEven).unwrap(), SafeDecimal::MAX);
+
+    let signed = [dec!(-1, SignedSafeDecimal), dec!(-2, SignedSafeDecimal)];
+    assert_eq!(signed.iter().try_mean(RoundingMode::Floor).unwrap(), dec!(-1.5, SignedSafeDecimal));
+
+    let error = std::iter::empty::<SafeDecimal>().try_mean(RoundingMode::HalfEven).unwrap_err();
+    assert!(matches!(error.into_error(), Error::DivisionByZero {}));
+  }
+}
@@ -1,5 +1,29 @@
+mod allocate;
+mod arithmetic;
 mod decimal;
 mod error;
+mod fixed;
+mod format;
+mod iter;
+mod locale;
+mod macros;
+mod parse;
+mod power;
+mod quantize;
+mod ratio;
+mod rounding;
+pub mod serde;
+mod signed;
+mod wide;
 
-pub use decimal::SafeDecimal;
-pub use error::{Error, Result};
+pub use allocate::Leftover;
+pub use decimal::{Decimal, SafeDecimal};
+pub use error::{Error, ItemError, ParseError, ParseErrorKind, Result};
+pub use fixed::FixedPoint;
+pub use format::{Engineering, MAX_STR_LEN};
+pub use iter::DecimalIterator;
+pub use locale::{Formatted, NumberFormat};
+pub use parse::ParseOptions;
+pub use ratio::SafeRatio;
+pub use rounding::RoundingMode;
+pub use signed::{SignedDecimal, SignedSafeDecimal};
@@ -0,0 +1,451 @@
+//! Locale-style formatting with digit grouping, a custom decimal mark and fraction digit
+//! limits, and the matching parse path.
+
+use std::fmt::{Display, Formatter, Result as FmtResult, Write};
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::format::{pad, write_repeated};
+use crate::parse::{parse, ParseOptions, Parsed};
+use crate::rounding::RoundingMode;
+
+/// Grouping, decimal mark and fraction digits for [`Decimal::format`](crate::Decimal::format)
+/// and [`Decimal::parse_format`](crate::Decimal::parse_format). The default matches
+/// `Display`. The group separator and decimal point must differ, so set the decimal point
+/// first when grouping with `.`.
+///
+/// ```
+/// use perfect_decimal::{dec, NumberFormat, SafeDecimal};
+///
+/// let german = NumberFormat::new().decimal_point(',').grouping('.', 3).min_fraction(2);
+/// assert_eq!(dec!(1234567.5).format().with(german).to_string(), "1.234.567,50");
+/// assert_eq!(SafeDecimal::parse_format("1.234.567,50", german).unwrap(), dec!(1234567.5));
+///
+/// let indian = dec!(1234567.5).format().indian_grouping(',').max_fraction(0);
+/// assert_eq!(indian.to_string(), "12,34,568");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NumberFormat {
+  separator: Option<char>,
+  primary_group: u8,
+  secondary_group: u8,
+  decimal_point: char,
+  min_fraction: u32,
+  max_fraction: Option<u32>,
+  rounding: RoundingMode,
+}
+
+impl NumberFormat {
+  pub const fn new() -> Self {
+    Self {
+      separator: None,
+      primary_group: 3,
+      secondary_group: 3,
+      decimal_point: '.',
+      min_fraction: 0,
+      max_fraction: None,
+      rounding: RoundingMode::HalfEven,
+    }
+  }
+
+  /// Separates groups of `size` integral digits with `separator`, as in `1,234,567`.
+  ///
+  /// # Panics
+  ///
+  /// If `size` is zero, or `separator` is a digit, a sign, ASCII whitespace or the decimal
+  /// point.
+  pub const fn grouping(mut self, separator: char, size: u8) -> Self {
+    assert!(size > 0, "group size must be positive");
+    self.separator = Some(self.check_separator(separator));
+    self.primary_group = size;
+    self.secondary_group = size;
+    self
+  }
+
+  /// Indian grouping: the last three integral digits, then pairs, as in `12,34,567`.
+  ///
+  /// # Panics
+  ///
+  /// If `separator` is a digit, a sign, ASCII whitespace or the decimal point.
+  pub const fn indian_grouping(mut self, separator: char) -> Self {
+    self.separator = Some(self.check_separator(separator));
+    self.primary_group = 3;
+    self.secondary_group = 2;
+    self
+  }
+
+  /// # Panics
+  ///
+  /// If `decimal_point` is a digit, a sign, ASCII whitespace or the group separator.
+  pub const fn decimal_point(mut self, decimal_point: char) -> Self {
+    assert!(is_mark(decimal_point), "invalid decimal point");
+    if let Some(separator) = self.separator {
+      assert!(separator as u32 != decimal_point as u32, "group separator same as decimal point");
+    }
+    self.decimal_point = decimal_point;
+    self
+  }
+
+  /// Pads the fraction with zeros to at least `digits` digits, raising the maximum if needed.
+  pub const fn min_fraction(mut self, digits: u32) -> Self {
+    self.min_fraction = digits;
+    if let Some(max) = self.max_fraction {
+      if max < digits {
+        self.max_fraction = Some(digits);
+      }
+    }
+    self
+  }
+
+  /// Rounds the fraction to at most `digits` digits, lowering the minimum if needed.
+  pub const fn max_fraction(mut self, digits: u32) -> Self {
+    self.max_fraction = Some(digits);
+    if self.min_fraction > digits {
+      self.min_fraction = digits;
+    }
+    self
+  }
+
+  /// How [`NumberFormat::max_fraction`] rounds, half to even by default like `Display`.
+  pub const fn rounding(mut self, mode: RoundingMode) -> Self {
+    self.rounding = mode;
+    self
+  }
+
+  const fn check_separator(&self, separator: char) -> char {
+    assert!(is_mark(separator), "invalid group separator");
+    assert!(separator as u32 != self.decimal_point as u32, "group separator same as decimal point");
+    separator
+  }
+
+  /// Whether a separator belongs between the digit `right` places from the end of the
+  /// integral part and the one before it.
+  fn is_boundary(&self, right: usize) -> bool {
+    let (primary, secondary) = (self.primary_group as usize, self.secondary_group as usize);
+    right == primary || right > primary && (right - primary).is_multiple_of(secondary)
+  }
+}
+
+/// Whether `c` can mark groups or the decimal point without being read as part of the number.
+/// Non-ASCII spaces stay allowed for French-style grouping.
+const fn is_mark(c: char) -> bool {
+  !(c.is_ascii_digit() || c.is_ascii_whitespace() || c == '-' || c == '+')
+}
+
+impl Default for NumberFormat {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// A decimal paired with a [`NumberFormat`], displayed by it. Width, fill and alignment
+/// apply to the whole text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Formatted {
+  negative: bool,
+  magnitude: u64,
+  frac_digits: u32,
+  format: NumberFormat,
+}
+
+impl Formatted {
+  pub(crate) fn new(negative: bool, magnitude: u64, frac_digits: u32) -> Self {
+    Self { negative, magnitude, frac_digits, format: NumberFormat::new() }
+  }
+
+  /// Replaces the whole format, e.g. with one shared across values.
+  pub fn with(mut self, format: NumberFormat) -> Self {
+    self.format = format;
+    self
+  }
+
+  /// See [`NumberFormat::grouping`].
+  pub fn grouping(self, separator: char, size: u8) -> Self {
+    self.with(self.format.grouping(separator, size))
+  }
+
+  /// See [`NumberFormat::indian_grouping`].
+  pub fn indian_grouping(self, separator: char) -> Self {
+    self.with(self.format.indian_grouping(separator))
+  }
+
+  /// See [`NumberFormat::decimal_point`].
+  pub fn decimal_point(self, decimal_point: char) -> Self {
+    self.with(self.format.decimal_point(decimal_point))
+  }
+
+  /// See [`NumberFormat::min_fraction`].
+  pub fn min_fraction(self, digits: u32) -> Self {
+    self.with(self.format.min_fraction(digits))
+  }
+
+  /// See [`NumberFormat::max_fraction`].
+  pub fn max_fraction(self, digits: u32) -> Self {
+    self.with(self.format.max_fraction(digits))
+  }
+
+  /// See [`NumberFormat::rounding`].
+  pub fn rounding(self, mode: RoundingMode) -> Self {
+    self.with(self.format.rounding(mode))
+  }
+}
+
+impl Display for Formatted {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    let format = &self.format;
+    let (magnitude, digits) = match format.max_fraction {
+      Some(max) if max < self.frac_digits => {
+        let divisor = 10i128.pow(self.frac_digits - max);
+        (format.rounding.divide(self.magnitude as i128, divisor) as u64, max)
+      }
+      _ => (self.magnitude, self.frac_digits),
+    };
+    let scale = 10u64.pow(digits);
+    let (integral, mut fraction, mut fraction_digits) =
+      (magnitude / scale, magnitude % scale, digits);
+    while fraction_digits > format.min_fraction && fraction.is_multiple_of(10) {
+      fraction /= 10;
+      fraction_digits -= 1;
+    }
+    let zeros = format.min_fraction.saturating_sub(fraction_digits) as usize;
+
+    let mut integral_digits = [0; 20];
+    let mut len = 0;
+    let mut rest = integral;
+    loop {
+      integral_digits[len] = b'0' + (rest % 10) as u8;
+      len += 1;
+      rest /= 10;
+      if rest == 0 {
+        break;
+      }
+    }
+    let separators = match format.separator {
+      Some(_) => (1..len).filter(|&right| format.is_boundary(right)).count(),
+      None => 0,
+    };
+    let fraction_len = fraction_digits as usize + zeros;
+    let chars =
+      self.negative as usize + len + separators + (fraction_len > 0) as usize + fraction_len;
+    pad(f, f.width().unwrap_or(0).saturating_sub(chars), |f| {
+      if self.negative {
+        f.write_str("-")?;
+      }
+      for right in (0..len).rev() {
+        f.write_char(integral_digits[right] as char)?;
+        if let Some(separator) = format.separator {
+          if right > 0 && format.is_boundary(right) {
+            f.write_char(separator)?;
+          }
+        }
+      }
+      if fraction_len > 0 {
+        f.write_char(format.decimal_point)?;
+        if fraction_digits > 0 {
+          f.write_fmt(format_args!("{fraction:0width$}", width = fraction_digits as usize))?;
+        }
+        write_repeated(f, '0', zeros)?;
+      }
+      Ok(())
+    })
+  }
+}
+
+/// Parses text written in `format`, by the same rules as [`FromStr`](std::str::FromStr)
+/// otherwise. Separators may be left out, but if present must sit at every group boundary.
+pub(crate) fn parse_format(
+  s: &str,
+  format: NumberFormat,
+  signed: bool,
+  frac_digits: u32,
+  max_units: u64,
+) -> Result<Parsed, ParseError> {
+  // Rewrite into canonical text, remembering where each byte came from to report errors
+  // against the original input.
+  let mut canonical = String::with_capacity(s.len());
+  let mut offsets = Vec::with_capacity(s.len());
+  let mut separators = Vec::new();
+  let mut integral_digits = 0;
+  let mut fraction = false;
+  for (offset, c) in s.char_indices() {
+    if c == format.decimal_point {
+      fraction = true;
+      canonical.push('.');
+      offsets.push(offset);
+    } else if Some(c) == format.separator && !fraction {
+      separators.push((integral_digits, offset));
+    } else if c == '.' || Some(c) == format.separator {
+      return Err(ParseError::new(ParseErrorKind::InvalidDigit, s.as_bytes(), offset));
+    } else {
+      integral_digits += (!fraction && c.is_ascii_digit()) as usize;
+      canonical.push(c);
+      offsets.extend(std::iter::repeat_n(offset, c.len_utf8()));
+    }
+  }
+  offsets.push(s.len());
+
+  let boundaries = (1..integral_digits).filter(|&right| format.is_boundary(right)).count();
+  let misplaced = separators.iter().enumerate().find(|&(i, &(before, _))| {
+    let right = integral_digits - before;
+    before == 0
+      || right == 0
+      || !format.is_boundary(right)
+      || i > 0 && separators[i - 1].0 == before
+  });
+  if let Some((_, &(_, offset))) = misplaced {
+    return Err(ParseError::new(ParseErrorKind::InvalidDigit, s.as_bytes(), offset));
+  }
+  if !separators.is_empty() && separators.len() != boundaries {
+    let offset = separators[0].1;
+    return Err(ParseError::new(ParseErrorKind::InvalidDigit, s.as_bytes(), offset));
+  }
+
+  parse(&canonical, ParseOptions::strict(), signed, frac_digits, max_units)
+    .map_err(|error| ParseError::new(error.kind(), s.as_bytes(), offsets[error.offset()]))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{dec, Error, SafeDecimal, SignedSafeDecimal};
+
+  const GERMAN: NumberFormat = NumberFormat::new().decimal_point(',').grouping('.', 3);
+  const INDIAN: NumberFormat = NumberFormat::new().indian_grouping(',');
+
+  fn parse_error(result: crate::Result<impl std::fmt::Debug>) -> (ParseErrorKind, usize) {
+    match result {
+      Err(Error::Parse(error)) => (error.kind(), error.offset()),
+      other => panic!("expected a parse error, got {other:?}"),
+    }
+  }
+
+  #[test]
+  fn test_default_matches_display() {
+    for value in [dec!(0), dec!(1.5), dec!(1234567.000001), SafeDecimal::MAX] {
+      assert_eq!(value.format().to_string(), value.to_string());
+    }
+  }
+
+  #[test]
+  fn test_grouping() {
+    let grouped = |value: SafeDecimal| value.format().grouping(',', 3).to_string();
+    assert_eq!(grouped(dec!(0)), "0");
+    assert_eq!(grouped(dec!(999)), "999");
+    assert_eq!(grouped(dec!(1000)), "1,000");
+    assert_eq!(grouped(dec!(1234567.891)), "1,234,567.891");
+    assert_eq!(grouped(SafeDecimal::MAX), "999,999,999.999999");
+    assert_eq!(dec!(1234567).format().grouping('\u{202f}', 4).to_string(), "123\u{202f}4567");
+    assert_eq!(dec!(-1234.5, SignedSafeDecimal).format().grouping(',', 3).to_string(), "-1,234.5");
+  }
+
+  #[test]
+  fn test_indian_grouping() {
+    let indian = |value: SafeDecimal| value.format().with(INDIAN).to_string();
+    assert_eq!(indian(dec!(123)), "123");
+    assert_eq!(indian(dec!(1234)), "1,234");
+    assert_eq!(indian(dec!(123456)), "1,23,456");
+    assert_eq!(indian(dec!(1234567.5)), "12,34,567.5");
+    assert_eq!(indian(dec!(999999999)), "99,99,99,999");
+  }
+
+  #[test]
+  fn test_fraction_digits() {
+    assert_eq!(dec!(1.5).format().with(GERMAN).min_fraction(2).to_string(), "1,50");
+    assert_eq!(dec!(7).format().min_fraction(2).to_string(), "7.00");
+    assert_eq!(dec!(1.5).format().min_fraction(8).to_string(), "1.50000000");
+    assert_eq!(dec!(1.005).format().max_fraction(2).to_string(), "1");
+    assert_eq!(dec!(1.005).format().max_fraction(2).min_fraction(2).to_string(), "1.00");
+    assert_eq!(dec!(1.015).format().max_fraction(2).to_string(), "1.02");
+    assert_eq!(dec!(2.5).format().max_fraction(0).to_string(), "2");
+    assert_eq!(dec!(2.5).format().max_fraction(0).rounding(RoundingMode::HalfUp).to_string(), "3");
+    assert_eq!(dec!(999.999).format().grouping(',', 3).max_fraction(2).to_string(), "1,000");
+    assert_eq!(dec!(1.23456).format().max_fraction(1).min_fraction(3).to_string(), "1.235");
+    assert_eq!(dec!(1.23456).format().min_fraction(3).max_fraction(1).to_string(), "1.2");
+    assert_eq!(dec!(-0.001, SignedSafeDecimal).format().max_fraction(2).to_string(), "-0");
+  }
+
+  #[test]
+  fn test_padding() {
+    let value = dec!(1234.5).format().grouping(',', 3);
+    assert_eq!(format!("{value:>10}|"), "   1,234.5|");
+    assert_eq!(format!("{value:*<10}"), "1,234.5***");
+    assert_eq!(format!("{value:^9}"), " 1,234.5 ");
+    assert_eq!(format!("{value:3}"), "1,234.5");
+  }
+
+  #[test]
+  fn test_parse_format() {
+    let german = |s| SafeDecimal::parse_format(s, GERMAN);
+    assert_eq!(german("1.234.567,50").unwrap(), dec!(1234567.5));
+    assert_eq!(german("1234567,5").unwrap(), dec!(1234567.5));
+    assert_eq!(german("999").unwrap(), dec!(999));
+    assert_eq!(parse_error(german("12.34.567")), (ParseErrorKind::InvalidDigit, 2));
+    assert_eq!(parse_error(german("1.234567")), (ParseErrorKind::InvalidDigit, 1));
+    assert_eq!(parse_error(german("1..234")), (ParseErrorKind::InvalidDigit, 2));
+    assert_eq!(parse_error(german(".123")), (ParseErrorKind::InvalidDigit, 0));
+    assert_eq!(parse_error(german("1.5")), (ParseErrorKind::InvalidDigit, 1));
+    assert_eq!(parse_error(german("1,5,5")), (ParseErrorKind::MultipleDots, 3));
+    assert_eq!(parse_error(german("1,5.5")), (ParseErrorKind::InvalidDigit, 3));
+    assert_eq!(parse_error(german("-1")), (ParseErrorKind::SignNotAllowed, 0));
+    assert_eq!(parse_error(german("1.000.000.000")), (ParseErrorKind::IntegralTooLarge, 0));
+    assert_eq!(parse_error(german("1,2345678")), (ParseErrorKind::TooManyFractionalDigits, 8));
+
+    let indian = |s| SafeDecimal::parse_format(s, INDIAN);
+    assert_eq!(indian("12,34,567.5").unwrap(), dec!(1234567.5));
+    assert_eq!(parse_error(indian("1,234,567")), (ParseErrorKind::InvalidDigit, 1));
+
+    let spaced = NumberFormat::new().grouping('\u{202f}', 3).decimal_point(',');
+    assert_eq!(
+      parse_error(SafeDecimal::parse_format("1\u{202f}234,5x", spaced)),
+      (ParseErrorKind::InvalidDigit, 9)
+    );
+    assert_eq!(
+      SignedSafeDecimal::parse_format("-1\u{202f}234,5", spaced).unwrap(),
+      dec!(-1234.5, SignedSafeDecimal)
+    );
+  }
+
+  #[test]
+  #[should_panic(expected = "group separator same as decimal point")]
+  fn test_separator_clash() {
+    let _ = NumberFormat::new().grouping(',', 3).decimal_point(',');
+  }
+
+  #[test]
+  #[should_panic(expected = "group separator same as decimal point")]
+  fn test_default_decimal_point_clash() {
+    let _ = NumberFormat::new().grouping('.', 3);
+  }
+
+  #[test]
+  #[should_panic(expected = "group separator same as decimal point")]
+  fn test_indian_separator_clash() {
+    let _ = NumberFormat::new().decimal_point(',').indian_grouping(',');
+  }
+
+  #[test]
+  fn test_invalid_marks() {
+    for c in ['0', '7', '-', '+', ' ', '\t', '\n'] {
+      let grouping = std::panic::catch_unwind(|| NumberFormat::new().grouping(c, 3));
+      assert!(grouping.is_err(), "{c:?} as a separator");
+      let indian = std::panic::catch_unwind(|| NumberFormat::new().indian_grouping(c));
+      assert!(indian.is_err(), "{c:?} as an indian separator");
+      let decimal_point = std::panic::catch_unwind(|| NumberFormat::new().decimal_point(c));
+      assert!(decimal_point.is_err(), "{c:?} as a decimal point");
+    }
+    for c in [',', '\'', '_', '\u{a0}', '\u{202f}'] {
+      assert_eq!(NumberFormat::new().grouping(c, 3).separator, Some(c));
+    }
+  }
+
+  #[test]
+  fn test_round_trip() {
+    let formats = [NumberFormat::new(), GERMAN.min_fraction(2), INDIAN.min_fraction(6)];
+    for units in (0..=SafeDecimal::MAX.to_units()).step_by(79_199_937_773) {
+      let value = SafeDecimal::from_units(units);
+      for format in formats {
+        let text = value.format().with(format).to_string();
+        assert_eq!(SafeDecimal::parse_format(&text, format).unwrap(), value, "{text}");
+      }
+    }
+  }
+}
@@ -0,0 +1,68 @@
+/// Creates a decimal from a literal, validated at compile time by the same rules as
+/// [`FromStr`](std::str::FromStr). Produces a [`SafeDecimal`](crate::SafeDecimal) unless
+/// another decimal type is given after a comma, and works in const contexts.
+///
+/// ```
+/// use perfect_decimal::{dec, Decimal, SafeDecimal, SignedSafeDecimal};
+///
+/// const FEE: SafeDecimal = dec!(12.34);
+/// assert_eq!(FEE.to_string(), "12.34");
+/// assert_eq!(dec!(-0.5, SignedSafeDecimal).to_string(), "-0.5");
+/// assert_eq!(dec!(100000000000.5, Decimal<12, 3>).to_string(), "100000000000.5");
+/// ```
+///
+/// Literals the type cannot hold exactly fail to compile:
+///
+/// ```compile_fail
+/// let _ = perfect_decimal::dec!(0.1234567);
+/// ```
+///
+/// ```compile_fail
+/// let _ = perfect_decimal::dec!(1000000000);
+/// ```
+///
+/// ```compile_fail
+/// let _ = perfect_decimal::dec!(1e3);
+/// ```
+#[macro_export]
+macro_rules! dec {
+  ($value:literal) => {
+    $crate::dec!($value, $crate::SafeDecimal)
+  };
+  ($value:literal, $type:ty) => {{
+    const VALUE: $type = match <$type>::parse(stringify!($value)) {
+      Ok(value) => value,
+      Err(_) => panic!(concat!("invalid decimal literal: ", stringify!($value))),
+    };
+    VALUE
+  }};
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use crate::{Decimal, SafeDecimal, SignedSafeDecimal};
+
+  #[test]
+  fn test_dec_matches_from_str() {
+    assert_eq!(dec!(12.34), SafeDecimal::from_str("12.34").unwrap());
+    assert_eq!(dec!(0), SafeDecimal::ZERO);
+    assert_eq!(dec!(999999999.999999), SafeDecimal::MAX);
+    assert_eq!(dec!(0.000001), SafeDecimal::MIN_POSITIVE);
+    assert_eq!(dec!(1.2500000), SafeDecimal::from_str("1.25").unwrap());
+  }
+
+  #[test]
+  fn test_dec_other_types() {
+    assert_eq!(dec!(-3.25, SignedSafeDecimal), SignedSafeDecimal::from_str("-3.25").unwrap());
+    assert_eq!(dec!(4.5, SignedSafeDecimal), SignedSafeDecimal::from_str("4.5").unwrap());
+    assert_eq!(dec!(123456789012.345, Decimal<12, 3>).to_string(), "123456789012.345");
+  }
+
+  #[test]
+  fn test_dec_in_const() {
+    const LIMITS: [SafeDecimal; 2] = [dec!(0.5), dec!(100)];
+    assert_eq!(LIMITS[1].to_string(), "100");
+  }
+}
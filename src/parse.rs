@@ -0,0 +1,543 @@
+//! Decimal text parsing shared by [`FromStr`](std::str::FromStr), [`dec!`](crate::dec) and
+//! [`ParseOptions`].
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::rounding::RoundingMode;
+
+/// Which parts of the decimal text grammar to accept beyond the strict canonical form:
+///
+/// ```text
+/// input     = [space] [sign] mantissa [exponent] [space]
+/// sign      = "-" | "+"
+/// mantissa  = digits ["." [digits]] | "." digits
+/// digits    = digit {[separator] digit}
+/// exponent  = ("e" | "E") ["-" | "+"] digit {digit}
+/// ```
+///
+/// The strict mode used by `FromStr` accepts only `["-"] digit {digit} ["." digit {digit}]`,
+/// with `-` reserved for signed types. The other productions are enabled by the method of the
+/// same name, with `space` being ASCII whitespace. Fractional digits beyond the type's
+/// precision are accepted when they are zeros, and otherwise rejected unless a rounding mode
+/// is set.
+///
+/// ```
+/// use perfect_decimal::{ParseOptions, SafeDecimal};
+///
+/// let options = ParseOptions::strict().separator(Some(',')).trim_whitespace(true);
+/// let decimal = SafeDecimal::parse_with(" 1,234.5 ", options).unwrap();
+/// assert_eq!(decimal.to_string(), "1234.5");
+/// assert!(SafeDecimal::parse_with("1e3", options).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+  plus_sign: bool,
+  trim_whitespace: bool,
+  leading_dot: bool,
+  trailing_dot: bool,
+  exponent: bool,
+  separator: Option<u8>,
+  excess_zeros: bool,
+  rounding: Option<RoundingMode>,
+}
+
+impl ParseOptions {
+  /// The canonical form accepted by `FromStr`.
+  pub const fn strict() -> Self {
+    Self {
+      plus_sign: false,
+      trim_whitespace: false,
+      leading_dot: false,
+      trailing_dot: false,
+      exponent: false,
+      separator: None,
+      excess_zeros: true,
+      rounding: None,
+    }
+  }
+
+  /// Everything in the grammar, with `_` as the digit separator. Inexact input is still
+  /// rejected unless [`ParseOptions::rounding`] is set.
+  pub const fn lenient() -> Self {
+    Self {
+      plus_sign: true,
+      trim_whitespace: true,
+      leading_dot: true,
+      trailing_dot: true,
+      exponent: true,
+      separator: Some(b'_'),
+      excess_zeros: true,
+      rounding: None,
+    }
+  }
+
+  /// Accepts a leading `+`.
+  pub const fn plus_sign(mut self, enabled: bool) -> Self {
+    self.plus_sign = enabled;
+    self
+  }
+
+  /// Ignores leading and trailing ASCII whitespace.
+  pub const fn trim_whitespace(mut self, enabled: bool) -> Self {
+    self.trim_whitespace = enabled;
+    self
+  }
+
+  /// Accepts a missing integral part, as in `.5`.
+  pub const fn leading_dot(mut self, enabled: bool) -> Self {
+    self.leading_dot = enabled;
+    self
+  }
+
+  /// Accepts a dot without fractional digits, as in `5.`.
+  pub const fn trailing_dot(mut self, enabled: bool) -> Self {
+    self.trailing_dot = enabled;
+    self
+  }
+
+  /// Accepts a decimal exponent, as in `1.5e3` or `15E-1`.
+  pub const fn exponent(mut self, enabled: bool) -> Self {
+    self.exponent = enabled;
+    self
+  }
+
+  /// Accepts single `separator` characters between digits, as in `1_000` or `1,000`. Group
+  /// sizes are not checked.
+  ///
+  /// # Panics
+  ///
+  /// If `separator` is not ASCII punctuation.
+  pub const fn separator(mut self, separator: Option<char>) -> Self {
+    self.separator = match separator {
+      Some(separator) => {
+        assert!(separator.is_ascii_punctuation() && separator != '.', "invalid digit separator");
+        Some(separator as u8)
+      }
+      None => None,
+    };
+    self
+  }
+
+  /// Accepts zeros beyond the type's fractional digits, as in `1.50000000`. Enabled in both
+  /// presets, since no precision is lost.
+  pub const fn excess_zeros(mut self, enabled: bool) -> Self {
+    self.excess_zeros = enabled;
+    self
+  }
+
+  /// Rounds excess fractional digits by `mode` instead of rejecting them.
+  pub const fn rounding(mut self, mode: Option<RoundingMode>) -> Self {
+    self.rounding = mode;
+    self
+  }
+}
+
+impl ParseOptions {
+  /// ECMA-262 regular expression matching at least every input these options accept for a
+  /// type with the given digits. Without exponents it also bounds the digit counts, so it
+  /// matches exactly the accepted inputs, except for values that round past the maximum.
+  pub(crate) fn pattern(&self, signed: bool, int_digits: u32, frac_digits: u32) -> String {
+    // Separators are optional between any two digits, and in a class to avoid escaping
+    // rules that differ between regex flavours.
+    let separator = match self.separator.map(char::from) {
+      Some(c @ ('\\' | ']' | '^' | '-')) => format!("[\\{c}]?"),
+      Some(c) => format!("[{c}]?"),
+      None => String::new(),
+    };
+    let run = |first: &str, digit: &str, count: &str| match separator.as_str() {
+      "" => format!("{first}{digit}{count}"),
+      separator => format!("{first}({separator}{digit}){count}"),
+    };
+    let any = run("[0-9]", "[0-9]", "*");
+    let zeros = run("0", "0", "*");
+    let (integral, fraction) = if self.exponent {
+      (any.clone(), Some(any))
+    } else {
+      let integral = match int_digits {
+        0 => zeros.clone(),
+        digits => {
+          let significant = run("[1-9]", "[0-9]", &format!("{{0,{}}}", digits - 1));
+          format!("({zeros}|({zeros}{separator})?{significant})")
+        }
+      };
+      let fraction = match (self.rounding, frac_digits, self.excess_zeros) {
+        (Some(_), _, _) => Some(any),
+        (None, 0, true) => Some(zeros),
+        (None, 0, false) => None,
+        (None, digits, excess_zeros) => {
+          let digits = run("[0-9]", "[0-9]", &format!("{{0,{}}}", digits - 1));
+          let zeros = if excess_zeros { format!("({separator}0)*") } else { String::new() };
+          Some(format!("{digits}{zeros}"))
+        }
+      };
+      (integral, fraction)
+    };
+    let dot = if self.trailing_dot { "?" } else { "" };
+    let mantissa = match fraction {
+      Some(fraction) if self.leading_dot => {
+        format!("({integral}(\\.({fraction}){dot})?|\\.{fraction})")
+      }
+      Some(fraction) => format!("{integral}(\\.({fraction}){dot})?"),
+      None if self.trailing_dot => format!("{integral}\\.?"),
+      None => integral,
+    };
+    let sign = match (signed, self.plus_sign) {
+      (true, true) => "[-+]?",
+      (true, false) => "-?",
+      (false, true) => "\\+?",
+      (false, false) => "",
+    };
+    let exponent = if self.exponent { "([eE][-+]?[0-9]+)?" } else { "" };
+    let space = if self.trim_whitespace { "[ \\t\\n\\f\\r]*" } else { "" };
+    format!("^{space}{sign}{mantissa}{exponent}{space}$")
+  }
+}
+
+impl Default for ParseOptions {
+  fn default() -> Self {
+    Self::strict()
+  }
+}
+
+/// Parsed magnitude in units of `10^-frac_digits`, with the dropped digits as a tail in the
+/// format of [`Parsed::hundredths`].
+pub(crate) struct Parsed {
+  pub(crate) negative: bool,
+  pub(crate) units: u64,
+  pub(crate) tail: u8,
+}
+
+impl Parsed {
+  /// Signed value in hundredths of a unit, plus one if any later dropped digit is non-zero,
+  /// which is all any rounding mode needs.
+  pub(crate) fn hundredths(&self) -> i128 {
+    let hundredths = self.units as i128 * 100 + self.tail as i128;
+    if self.negative {
+      -hundredths
+    } else {
+      hundredths
+    }
+  }
+
+  /// Applies the rounding mode of `options`, which the parse only leaves a tail for when set.
+  pub(crate) fn rounded(&self, options: ParseOptions) -> i128 {
+    match options.rounding {
+      Some(mode) => mode.divide(self.hundredths(), 100),
+      None => self.hundredths() / 100,
+    }
+  }
+}
+
+/// Parses `s` by `options` into at most `max_units` units of `10^-frac_digits`, rejecting a
+/// minus sign unless `signed`.
+pub(crate) const fn parse(
+  s: &str,
+  options: ParseOptions,
+  signed: bool,
+  frac_digits: u32,
+  max_units: u64,
+) -> Result<Parsed, ParseError> {
+  let bytes = s.as_bytes();
+  let (mut begin, mut end) = (0, bytes.len());
+  while options.trim_whitespace && begin < end && bytes[begin].is_ascii_whitespace() {
+    begin += 1;
+  }
+  while options.trim_whitespace && end > begin && bytes[end - 1].is_ascii_whitespace() {
+    end -= 1;
+  }
+  if begin == end {
+    return Err(ParseError::new(ParseErrorKind::Empty, bytes, begin));
+  }
+  let negative = bytes[begin] == b'-';
+  if negative && !signed || bytes[begin] == b'+' && !options.plus_sign {
+    return Err(ParseError::new(ParseErrorKind::SignNotAllowed, bytes, begin));
+  }
+  let start = if negative || bytes[begin] == b'+' { begin + 1 } else { begin };
+
+  // Validate the structure, counting digits and reading the exponent.
+  let mut int_digits = 0;
+  let mut dot = None;
+  let mut frac_count = 0;
+  let mut i = start;
+  while i < end {
+    match bytes[i] {
+      b'0'..=b'9' if dot.is_some() => frac_count += 1,
+      b'0'..=b'9' => int_digits += 1,
+      b'.' if dot.is_none() => dot = Some(i),
+      b'.' => return Err(ParseError::new(ParseErrorKind::MultipleDots, bytes, i)),
+      b'e' | b'E' if options.exponent => break,
+      byte if is_separator(options, bytes, i, end, byte) => {}
+      _ => return Err(ParseError::new(ParseErrorKind::InvalidDigit, bytes, i)),
+    }
+    i += 1;
+  }
+  if int_digits == 0 && (frac_count == 0 || !options.leading_dot) {
+    return Err(ParseError::new(ParseErrorKind::MissingDigits, bytes, start));
+  }
+  if let Some(dot) = dot {
+    if frac_count == 0 && !options.trailing_dot {
+      return Err(ParseError::new(ParseErrorKind::MissingDigits, bytes, dot + 1));
+    }
+  }
+  let mantissa_end = i;
+  let mut exponent: i64 = 0;
+  if i < end {
+    i += 1;
+    let exponent_negative = i < end && bytes[i] == b'-';
+    if i < end && (bytes[i] == b'-' || bytes[i] == b'+') {
+      i += 1;
+    }
+    if i == end {
+      return Err(ParseError::new(ParseErrorKind::MissingDigits, bytes, i));
+    }
+    while i < end {
+      if !bytes[i].is_ascii_digit() {
+        return Err(ParseError::new(ParseErrorKind::InvalidDigit, bytes, i));
+      }
+      // Anything this large over- or underflows every configuration alike.
+      if exponent < u32::MAX as i64 {
+        exponent = exponent * 10 + (bytes[i] - b'0') as i64;
+      }
+      i += 1;
+    }
+    if exponent_negative {
+      exponent = -exponent;
+    }
+  }
+
+  // Place each digit `position` places after the point, accumulating those within
+  // `frac_digits` into units and the rest into the tail.
+  let too_large = ParseError::new(ParseErrorKind::IntegralTooLarge, bytes, start);
+  let mut units: u64 = 0;
+  let mut last_position = frac_digits as i64;
+  let mut tail = 0;
+  let mut excess = None;
+  let mut position = 1 - int_digits as i64 - exponent;
+  let mut i = start;
+  while i < mantissa_end {
+    let byte = bytes[i];
+    i += 1;
+    if !byte.is_ascii_digit() {
+      continue;
+    }
+    let digit = byte - b'0';
+    if position <= frac_digits as i64 {
+      units = units * 10 + digit as u64;
+      if units > max_units {
+        return Err(too_large);
+      }
+      last_position = position;
+    } else {
+      if excess.is_none() {
+        excess = Some(i - 1);
+      }
+      if position == frac_digits as i64 + 1 {
+        tail = digit * 10;
+      } else if digit != 0 {
+        tail |= 1;
+      }
+    }
+    position += 1;
+  }
+  let shift = frac_digits as i64 - last_position;
+  if units != 0 && shift > 0 {
+    let scaled = if shift <= 19 { units.checked_mul(10u64.pow(shift as u32)) } else { None };
+    units = match scaled {
+      Some(units) if units <= max_units => units,
+      _ => return Err(too_large),
+    };
+  }
+  if let Some(offset) = excess {
+    if options.rounding.is_none() && (tail != 0 || !options.excess_zeros) {
+      return Err(ParseError::new(ParseErrorKind::TooManyFractionalDigits, bytes, offset));
+    }
+  }
+  Ok(Parsed { negative, units, tail })
+}
+
+const fn is_separator(options: ParseOptions, bytes: &[u8], i: usize, end: usize, byte: u8) -> bool {
+  matches!(options.separator, Some(separator) if separator == byte)
+    && i > 0
+    && bytes[i - 1].is_ascii_digit()
+    && i + 1 < end
+    && bytes[i + 1].is_ascii_digit()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use super::*;
+  use crate::{Decimal, Error, Result, SafeDecimal, SignedSafeDecimal};
+
+  fn kind<T: std::fmt::Debug>(result: Result<T>) -> ParseErrorKind {
+    match result {
+      Err(Error::Parse(error)) => error.kind(),
+      other => panic!("expected a parse error, got {other:?}"),
+    }
+  }
+
+  fn lenient(s: &str) -> Result<String> {
+    SafeDecimal::parse_with(s, ParseOptions::lenient()).map(|decimal| decimal.to_string())
+  }
+
+  #[test]
+  fn test_strict() {
+    for input in ["+5", "5.", ".5", " 1", "1 ", "1e3", "1_000", "-1", "", ".", "-", "1..2", "0x1"] {
+      assert!(SafeDecimal::from_str(input).is_err(), "{input:?}");
+    }
+    assert_eq!(SafeDecimal::from_str("007.50").unwrap().to_string(), "7.5");
+    assert_eq!(SignedSafeDecimal::from_str("-1.5").unwrap().to_string(), "-1.5");
+    assert!(SignedSafeDecimal::from_str("+1.5").is_err());
+    assert!(SignedSafeDecimal::from_str("--1").is_err());
+  }
+
+  #[test]
+  fn test_lenient() {
+    assert_eq!(lenient("+5").unwrap(), "5");
+    assert_eq!(lenient("5.").unwrap(), "5");
+    assert_eq!(lenient(".5").unwrap(), "0.5");
+    assert_eq!(lenient(" \t1.25\n").unwrap(), "1.25");
+    assert_eq!(lenient("1_000_000.000_1").unwrap(), "1000000.0001");
+    assert!(lenient(".").is_err());
+    assert!(lenient("+").is_err());
+    assert!(lenient("- 1").is_err());
+    assert!(lenient("-1").is_err());
+    let signed = |s| SignedSafeDecimal::parse_with(s, ParseOptions::lenient()).unwrap().to_string();
+    assert_eq!(signed(" -.5 "), "-0.5");
+    assert_eq!(signed("-1_000e-3"), "-1");
+  }
+
+  #[test]
+  fn test_exponent() {
+    assert_eq!(lenient("1.5e3").unwrap(), "1500");
+    assert_eq!(lenient("15E-1").unwrap(), "1.5");
+    assert_eq!(lenient("1e+2").unwrap(), "100");
+    assert_eq!(lenient("1e-6").unwrap(), "0.000001");
+    assert_eq!(lenient("123456.7e-5").unwrap(), "1.234567");
+    assert_eq!(lenient("0.0000001e1").unwrap(), "0.000001");
+    assert_eq!(lenient("9.99999999999999e8").unwrap(), "999999999.999999");
+    assert_eq!(lenient("0e99999999999999999999").unwrap(), "0");
+    assert_eq!(kind(lenient("1e-7")), ParseErrorKind::TooManyFractionalDigits);
+    assert_eq!(kind(lenient("1e9")), ParseErrorKind::IntegralTooLarge);
+    assert_eq!(kind(lenient("1e99999999999999999999")), ParseErrorKind::IntegralTooLarge);
+    assert_eq!(kind(lenient("1e-99999999999999999999")), ParseErrorKind::TooManyFractionalDigits);
+    for input in ["1e", "1e+", "e5", "1e5.0", "1e_5", "1.5e3e3"] {
+      assert!(lenient(input).is_err(), "{input:?}");
+    }
+    let rounded = ParseOptions::lenient().rounding(Some(RoundingMode::HalfUp));
+    assert_eq!(SafeDecimal::parse_with("5e-7", rounded).unwrap().to_string(), "0.000001");
+    assert_eq!(SafeDecimal::parse_with("4.9e-7", rounded).unwrap().to_string(), "0");
+  }
+
+  #[test]
+  fn test_separator() {
+    let commas = ParseOptions::strict().separator(Some(','));
+    assert_eq!(SafeDecimal::parse_with("1,234,567.5", commas).unwrap().to_string(), "1234567.5");
+    for input in ["1,,000", ",1", "1,", "1,.5", "1.,5", "1_000"] {
+      assert!(SafeDecimal::parse_with(input, commas).is_err(), "{input:?}");
+    }
+    assert!(SafeDecimal::parse_with("1,000", ParseOptions::lenient().separator(None)).is_err());
+  }
+
+  #[test]
+  fn test_excess_zeros() {
+    let exact = ParseOptions::strict().excess_zeros(false);
+    assert_eq!(SafeDecimal::parse_with("1.500000", exact).unwrap().to_string(), "1.5");
+    assert_eq!(
+      kind(SafeDecimal::parse_with("1.5000000", exact)),
+      ParseErrorKind::TooManyFractionalDigits
+    );
+    assert!(Decimal::<15, 0>::parse_with("123.0", exact).is_err());
+    assert_eq!(
+      Decimal::<15, 0>::parse_with("123.0", ParseOptions::strict()).unwrap().to_string(),
+      "123"
+    );
+    let rounded = exact.rounding(Some(RoundingMode::Down));
+    assert_eq!(SafeDecimal::parse_with("1.5000000", rounded).unwrap().to_string(), "1.5");
+  }
+
+  #[test]
+  fn test_defaults() {
+    assert_eq!(ParseOptions::default(), ParseOptions::strict());
+    const OPTIONS: ParseOptions = ParseOptions::lenient().exponent(false);
+    assert!(SafeDecimal::parse_with("1e3", OPTIONS).is_err());
+  }
+
+  #[test]
+  fn test_error_kinds() {
+    let cases = [
+      ("", ParseErrorKind::Empty, 0, ""),
+      ("12x4", ParseErrorKind::InvalidDigit, 2, "x4"),
+      (" 1", ParseErrorKind::InvalidDigit, 0, " 1"),
+      ("1.2.3", ParseErrorKind::MultipleDots, 3, ".3"),
+      ("-1", ParseErrorKind::SignNotAllowed, 0, "-1"),
+      ("+1", ParseErrorKind::SignNotAllowed, 0, "+1"),
+      (".5", ParseErrorKind::MissingDigits, 0, ".5"),
+      ("5.", ParseErrorKind::MissingDigits, 2, ""),
+      ("1000000000", ParseErrorKind::IntegralTooLarge, 0, "1000000000"),
+      ("0.1234567", ParseErrorKind::TooManyFractionalDigits, 8, "7"),
+      ("1.5e3", ParseErrorKind::InvalidDigit, 3, "e3"),
+      ("12é", ParseErrorKind::InvalidDigit, 2, "é"),
+    ];
+    for (input, expected, offset, snippet) in cases {
+      match SafeDecimal::from_str(input) {
+        Err(Error::Parse(error)) => {
+          assert_eq!(error.kind(), expected, "{input:?}");
+          assert_eq!(error.offset(), offset, "{input:?}");
+          assert_eq!(error.snippet(), snippet, "{input:?}");
+        }
+        other => panic!("{input:?} gave {other:?}"),
+      }
+    }
+    let lenient = |s| kind(SignedSafeDecimal::parse_with(s, ParseOptions::lenient()));
+    assert_eq!(lenient("  \t"), ParseErrorKind::Empty);
+    assert_eq!(lenient("-"), ParseErrorKind::MissingDigits);
+    assert_eq!(lenient("1e"), ParseErrorKind::MissingDigits);
+    assert_eq!(lenient("1e5x"), ParseErrorKind::InvalidDigit);
+    assert_eq!(lenient("1__0"), ParseErrorKind::InvalidDigit);
+    assert_eq!(lenient("-1e9"), ParseErrorKind::IntegralTooLarge);
+  }
+
+  #[test]
+  fn test_error_display() {
+    let error = SafeDecimal::from_str("1.5 USD").unwrap_err();
+    assert_eq!(error.to_string(), "Invalid digit at byte 3: \" USD\"");
+    let error = SafeDecimal::from_str("123456789.12345678901234567890123").unwrap_err();
+    assert_eq!(error.to_string(), "Too many fractional digits at byte 16: \"7890123456789012\"...");
+    let error = SafeDecimal::from_str("1.000000000000000€").unwrap_err();
+    assert_eq!(error.to_string(), "Invalid digit at byte 17: \"€\"");
+    let error = SafeDecimal::from_str("1x23456789012345€").unwrap_err();
+    if let Error::Parse(error) = error {
+      assert_eq!(error.snippet(), "x23456789012345");
+    }
+  }
+
+  #[test]
+  fn test_pattern() {
+    assert_eq!(
+      ParseOptions::strict().pattern(false, 9, 6),
+      r"^(00*|(00*)?[1-9][0-9]{0,8})(\.([0-9][0-9]{0,5}(0)*))?$"
+    );
+    assert_eq!(
+      ParseOptions::strict().excess_zeros(false).pattern(true, 2, 0),
+      r"^-?(00*|(00*)?[1-9][0-9]{0,1})$"
+    );
+    assert_eq!(
+      ParseOptions::strict()
+        .plus_sign(true)
+        .leading_dot(true)
+        .trailing_dot(true)
+        .pattern(false, 0, 2),
+      r"^\+?(00*(\.([0-9][0-9]{0,1}(0)*)?)?|\.[0-9][0-9]{0,1}(0)*)$"
+    );
+    assert_eq!(
+      ParseOptions::strict().separator(Some('_')).pattern(false, 3, 1),
+      r"^(0([_]?0)*|(0([_]?0)*[_]?)?[1-9]([_]?[0-9]){0,2})(\.([0-9]([_]?[0-9]){0,0}([_]?0)*))?$"
+    );
+    assert_eq!(
+      ParseOptions::lenient().pattern(true, 9, 6),
+      r"^[ \t\n\f\r]*[-+]?([0-9]([_]?[0-9])*(\.([0-9]([_]?[0-9])*)?)?|\.[0-9]([_]?[0-9])*)([eE][-+]?[0-9]+)?[ \t\n\f\r]*$"
+    );
+    assert!(ParseOptions::strict().separator(Some('-')).pattern(false, 1, 1).contains(r"[\-]?"));
+  }
+}
@@ -0,0 +1,663 @@
+//! Integer powers and roots, rounded once from exact comparisons.
+//!
+//! Powers whose exact decimal expansion is short enough to sit on a rounding boundary are
+//! computed exactly. All others are bracketed between binary fixed-point bounds, doubling the
+//! precision until both bounds round the same way. Such a power is positive and off every
+//! rounding boundary except zero, which the lower bound truncates to until the bits reach
+//! about `log2(1 / power)`, so a zero lower bound is taken as a power below half a unit and
+//! the loop terminates. Roots search for the result units by comparing their powers to the
+//! radicand in the same way.
+
+use std::cmp::Ordering;
+
+use crate::decimal::Decimal;
+use crate::error::{Error, Result};
+use crate::rounding::RoundingMode;
+use crate::signed::SignedDecimal;
+
+/// Fraction bits of the first attempt at bracketing a power.
+const INITIAL_PRECISION: u32 = 128;
+
+/// Integer bits past which a power certainly exceeds every decimal.
+const MAX_INTEGER_BITS: u32 = 64;
+
+/// Unsigned big integer, least significant limb first, without leading zero limbs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Natural(Vec<u64>);
+
+impl Natural {
+  fn from_u128(value: u128) -> Self {
+    Self(vec![value as u64, (value >> 64) as u64]).normalized()
+  }
+
+  fn normalized(mut self) -> Self {
+    while self.0.last() == Some(&0) {
+      self.0.pop();
+    }
+    self
+  }
+
+  fn to_u128(&self) -> Option<u128> {
+    match self.0[..] {
+      [] => Some(0),
+      [low] => Some(low as u128),
+      [low, high] => Some(low as u128 | (high as u128) << 64),
+      _ => None,
+    }
+  }
+
+  fn bits(&self) -> u32 {
+    self.0.last().map_or(0, |top| self.0.len() as u32 * 64 - top.leading_zeros())
+  }
+
+  fn bit(&self, index: u32) -> bool {
+    self.0.get((index / 64) as usize).is_some_and(|limb| limb >> (index % 64) & 1 != 0)
+  }
+
+  /// Whether any of the lowest `count` bits is set.
+  fn any_below(&self, count: u32) -> bool {
+    let (whole, rest) = ((count / 64) as usize, count % 64);
+    self.0.iter().take(whole).any(|&limb| limb != 0)
+      || rest != 0 && self.0.get(whole).is_some_and(|&limb| limb & ((1 << rest) - 1) != 0)
+  }
+
+  fn shl(&self, count: u32) -> Self {
+    let (whole, rest) = ((count / 64) as usize, count % 64);
+    let mut limbs = vec![0; whole];
+    let mut carry = 0;
+    for &limb in &self.0 {
+      limbs.push(limb << rest | carry);
+      carry = if rest == 0 { 0 } else { limb >> (64 - rest) };
+    }
+    limbs.push(carry);
+    Self(limbs).normalized()
+  }
+
+  /// Shifts right by `count` bits, rounding down or, with `ceil`, up.
+  fn shr(&self, count: u32, ceil: bool) -> Self {
+    let (whole, rest) = ((count / 64) as usize, count % 64);
+    let limbs = self.0.get(whole..).unwrap_or_default();
+    let shifted = (0..limbs.len())
+      .map(|i| {
+        let high =
+          if rest == 0 { 0 } else { limbs.get(i + 1).map_or(0, |&limb| limb << (64 - rest)) };
+        limbs[i] >> rest | high
+      })
+      .collect();
+    let shifted = Self(shifted).normalized();
+    if ceil && self.any_below(count) {
+      shifted.add_one()
+    } else {
+      shifted
+    }
+  }
+
+  fn add_one(mut self) -> Self {
+    for limb in &mut self.0 {
+      let (sum, carry) = limb.overflowing_add(1);
+      *limb = sum;
+      if !carry {
+        return self;
+      }
+    }
+    self.0.push(1);
+    self
+  }
+
+  fn mul(&self, other: &Self) -> Self {
+    let mut limbs = vec![0; self.0.len() + other.0.len()];
+    for (i, &a) in self.0.iter().enumerate() {
+      let mut carry = 0;
+      for (j, &b) in other.0.iter().enumerate() {
+        let product = a as u128 * b as u128 + limbs[i + j] as u128 + carry;
+        limbs[i + j] = product as u64;
+        carry = product >> 64;
+      }
+      limbs[i + other.0.len()] = carry as u64;
+    }
+    Self(limbs).normalized()
+  }
+
+  /// Quotient by a non-zero `divisor`, and whether the division left a remainder.
+  fn div_rem(&self, divisor: u64) -> (Self, bool) {
+    let mut limbs = vec![0; self.0.len()];
+    let mut remainder = 0u128;
+    for i in (0..self.0.len()).rev() {
+      let dividend = remainder << 64 | self.0[i] as u128;
+      limbs[i] = (dividend / divisor as u128) as u64;
+      remainder = dividend % divisor as u128;
+    }
+    (Self(limbs).normalized(), remainder != 0)
+  }
+}
+
+impl Ord for Natural {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.0.len().cmp(&other.0.len()).then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+  }
+}
+
+impl PartialOrd for Natural {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+/// Lower and upper bounds of `units / scale` with `precision` fraction bits.
+fn fixed(units: u128, scale: u64, precision: u32) -> (Natural, Natural) {
+  let (low, inexact) = Natural::from_u128(units).shl(precision).div_rem(scale);
+  let high = if inexact { low.clone().add_one() } else { low.clone() };
+  (low, high)
+}
+
+/// Bounds of `(units / scale)^exp` with `precision` fraction bits, or `None` when the lower
+/// bound already exceeds every decimal.
+fn pow_bounds(units: u128, scale: u64, exp: u32, precision: u32) -> Option<(Natural, Natural)> {
+  let (base_low, base_high) = fixed(units, scale, precision);
+  let one = Natural::from_u128(1).shl(precision);
+  let (mut low, mut high) = (one.clone(), one);
+  for bit in (0..u32::BITS - exp.leading_zeros()).rev() {
+    low = low.mul(&low).shr(precision, false);
+    high = high.mul(&high).shr(precision, true);
+    if exp >> bit & 1 != 0 {
+      low = low.mul(&base_low).shr(precision, false);
+      high = high.mul(&base_high).shr(precision, true);
+    }
+    // Partial powers only grow towards the result once the base is at least one.
+    if low.bits() > precision + MAX_INTEGER_BITS {
+      return None;
+    }
+  }
+  Some((low, high))
+}
+
+/// Rounds a fixed-point `value` with `precision` fraction bits to units of `1 / scale`.
+fn round_fixed(
+  value: &Natural,
+  precision: u32,
+  scale: u64,
+  mode: RoundingMode,
+  negative: bool,
+) -> Option<u128> {
+  let scaled = value.mul(&Natural::from_u128(scale as u128));
+  let quotient = scaled.shr(precision, false).to_u128()?;
+  if !scaled.any_below(precision) {
+    return Some(quotient);
+  }
+  // Quarters stand in for the remainder, enough to tell it apart from a half.
+  let remainder = match (scaled.bit(precision - 1), scaled.any_below(precision - 1)) {
+    (false, _) => 1,
+    (true, false) => 2,
+    (true, true) => 3,
+  };
+  quotient.checked_add(mode.away_from_zero(quotient % 2 != 0, remainder, 4, negative) as u128)
+}
+
+/// `units / 10^digits` in lowest terms, as numerator and remaining digits.
+fn reduce(mut units: u128, mut digits: u32) -> (u128, u32) {
+  while digits > 0 && units.is_multiple_of(10) {
+    units /= 10;
+    digits -= 1;
+  }
+  (units, digits)
+}
+
+/// `numerator / divisor` rounded by `mode` for a quotient of sign `negative`.
+fn divide(numerator: u128, divisor: u128, mode: RoundingMode, negative: bool) -> Option<u128> {
+  let (quotient, remainder) = (numerator / divisor, numerator % divisor);
+  if remainder == 0 {
+    return Some(quotient);
+  }
+  quotient.checked_add(mode.away_from_zero(quotient % 2 != 0, remainder, divisor, negative) as u128)
+}
+
+/// Units of `(units / 10^frac_digits)^exp` rounded by `mode`, or `None` past `u128`.
+fn pow_units(
+  units: u128,
+  frac_digits: u32,
+  exp: u32,
+  mode: RoundingMode,
+  negative: bool,
+) -> Option<u128> {
+  let scale = 10u64.pow(frac_digits);
+  if exp == 0 {
+    return Some(scale as u128);
+  }
+  let (numerator, digits) = reduce(units, frac_digits);
+  let exact_digits = digits as u64 * exp as u64;
+  let power = numerator.checked_pow(exp);
+  // Only a power with at most one digit past `frac_digits` can be a tie or exact, and such a
+  // power past `u128` is out of range.
+  if power.is_none() && exact_digits <= frac_digits as u64 + 1 {
+    return None;
+  }
+  let divisor = u32::try_from(exact_digits).ok().and_then(|digits| 10u128.checked_pow(digits));
+  if let (Some(power), Some(divisor)) = (power, divisor) {
+    let scale = scale as u128;
+    return if divisor >= scale {
+      divide(power, divisor / scale, mode, negative)
+    } else {
+      power.checked_mul(scale / divisor)
+    };
+  }
+  bracket_pow(numerator, digits, exp, frac_digits, mode, negative)
+}
+
+/// Like [`pow_units`] for a base in lowest terms whose power is not on a rounding boundary.
+fn bracket_pow(
+  numerator: u128,
+  digits: u32,
+  exp: u32,
+  frac_digits: u32,
+  mode: RoundingMode,
+  negative: bool,
+) -> Option<u128> {
+  let scale = 10u64.pow(frac_digits);
+  let mut precision = INITIAL_PRECISION;
+  loop {
+    let (low, high) = pow_bounds(numerator, 10u64.pow(digits), exp, precision)?;
+    // The power is positive, so a zero lower bound stands for some amount under half a unit.
+    let rounded = if low.0.is_empty() {
+      Some(mode.away_from_zero(false, 1, 4, negative) as u128)
+    } else {
+      round_fixed(&low, precision, scale, mode, negative)
+    };
+    if rounded == round_fixed(&high, precision, scale, mode, negative) {
+      return rounded;
+    }
+    precision *= 2;
+  }
+}
+
+/// Compares `(numerator / 10^digits)^exp` to `units / 10^frac_digits`.
+fn compare_pow(numerator: u128, digits: u32, exp: u32, units: u128, frac_digits: u32) -> Ordering {
+  let (numerator, digits) = reduce(numerator, digits);
+  let exact_digits = digits as u64 * exp as u64;
+  let power = numerator.checked_pow(exp);
+  // In lowest terms the power has `exact_digits` decimals, so past `u128` it either exceeds
+  // every decimal or has more digits than `units` and needs bracketing.
+  if exact_digits <= frac_digits as u64 {
+    let shift = 10u128.pow(frac_digits - exact_digits as u32);
+    return power
+      .and_then(|power| power.checked_mul(shift))
+      .map_or(Ordering::Greater, |power| power.cmp(&units));
+  }
+  let divisor = u32::try_from(exact_digits).ok().and_then(|digits| 10u128.checked_pow(digits));
+  let scaled = power.and_then(|power| power.checked_mul(10u128.pow(frac_digits)));
+  if let (Some(scaled), Some(units)) =
+    (scaled, divisor.and_then(|divisor| units.checked_mul(divisor)))
+  {
+    return scaled.cmp(&units);
+  }
+  bracket_compare(numerator, digits, exp, units, frac_digits)
+}
+
+/// Like [`compare_pow`] for a base in lowest terms whose power differs from `units`.
+fn bracket_compare(
+  numerator: u128,
+  digits: u32,
+  exp: u32,
+  units: u128,
+  frac_digits: u32,
+) -> Ordering {
+  let mut precision = INITIAL_PRECISION;
+  loop {
+    let Some((low, high)) = pow_bounds(numerator, 10u64.pow(digits), exp, precision) else {
+      return Ordering::Greater;
+    };
+    let (units_low, units_high) = fixed(units, 10u64.pow(frac_digits), precision);
+    if low > units_high {
+      return Ordering::Greater;
+    }
+    if high < units_low {
+      return Ordering::Less;
+    }
+    precision *= 2;
+  }
+}
+
+/// Units of the `n`th root of `units / 10^frac_digits` rounded by `mode`, for `n > 0`.
+fn root_units(
+  units: u128,
+  frac_digits: u32,
+  n: u32,
+  mode: RoundingMode,
+  negative: bool,
+) -> Option<u128> {
+  if n == 1 || units == 0 {
+    return Some(units);
+  }
+  // Searches for the largest root units whose power does not exceed the radicand, starting
+  // from a float estimate whose bracket is checked exactly, else from `0..=max(x, 1)`.
+  let scale = 10u128.pow(frac_digits) as f64;
+  let estimate = ((units as f64 / scale).powf(1.0 / n as f64) * scale) as u128;
+  let slack = estimate / 1_000_000_000 + 2;
+  let (mut low, mut high) = (estimate.saturating_sub(slack), estimate + slack);
+  if compare_pow(low, frac_digits, n, units, frac_digits) == Ordering::Greater {
+    low = 0;
+  }
+  if compare_pow(high, frac_digits, n, units, frac_digits) != Ordering::Greater {
+    high = units.max(10u128.pow(frac_digits));
+  }
+  while low < high {
+    let middle = low + (high - low).div_ceil(2);
+    match compare_pow(middle, frac_digits, n, units, frac_digits) {
+      Ordering::Greater => high = middle - 1,
+      _ => low = middle,
+    }
+  }
+  if compare_pow(low, frac_digits, n, units, frac_digits) == Ordering::Equal {
+    return Some(low);
+  }
+  // The midpoint has one more digit than the radicand in lowest terms, so it is never a tie.
+  let remainder = match mode {
+    RoundingMode::HalfEven | RoundingMode::HalfUp | RoundingMode::HalfDown => {
+      match compare_pow(low * 10 + 5, frac_digits + 1, n, units, frac_digits) {
+        Ordering::Less => 3,
+        _ => 1,
+      }
+    }
+    _ => 1,
+  };
+  low.checked_add(mode.away_from_zero(low % 2 != 0, remainder, 4, negative) as u128)
+}
+
+/// Units of the square root of `units / 10^frac_digits` rounded by `mode`.
+fn sqrt_units(units: u128, frac_digits: u32, mode: RoundingMode) -> Option<u128> {
+  let square = units.checked_mul(10u128.pow(frac_digits))?;
+  let root = square.isqrt();
+  let remainder = square - root * root;
+  if remainder == 0 {
+    return Some(root);
+  }
+  // `(root + 0.5)^2` lies strictly between two integers, so there are no ties.
+  let remainder = if remainder > root { 3 } else { 1 };
+  root.checked_add(mode.away_from_zero(root % 2 != 0, remainder, 4, false) as u128)
+}
+
+/// Powers and roots correctly rounded from the exact result, failing with
+/// [`Error::Overflow`] when that is out of range.
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Decimal<INT_DIGITS, FRAC_DIGITS> {
+  /// Raises to `exp`, rounding the exact power by `mode`. Anything to the power of zero is
+  /// one.
+  ///
+  /// ```
+  /// use perfect_decimal::{dec, RoundingMode};
+  ///
+  /// let growth = dec!(1.05).pow_rounded(10, RoundingMode::HalfEven).unwrap();
+  /// assert_eq!(growth, dec!(1.628895));
+  /// ```
+  pub fn pow_rounded(self, exp: u32, mode: RoundingMode) -> Result<Self> {
+    Self::checked(
+      pow_units(self.0 as u128, FRAC_DIGITS, exp, mode, false).ok_or(Error::Overflow {})?,
+    )
+  }
+
+  /// Raises to `exp`, truncating the exact power like [`Mul`](std::ops::Mul) does.
+  pub fn checked_pow(self, exp: u32) -> Option<Self> {
+    self.pow_rounded(exp, RoundingMode::Down).ok()
+  }
+
+  pub fn sqrt(self, mode: RoundingMode) -> Result<Self> {
+    Self::checked(sqrt_units(self.0 as u128, FRAC_DIGITS, mode).ok_or(Error::Overflow {})?)
+  }
+
+  /// The `n`th root, failing with [`Error::DivisionByZero`] for `n` of zero.
+  pub fn nth_root(self, n: u32, mode: RoundingMode) -> Result<Self> {
+    if n == 0 {
+      return Err(Error::DivisionByZero {});
+    }
+    Self::checked(
+      root_units(self.0 as u128, FRAC_DIGITS, n, mode, false).ok_or(Error::Overflow {})?,
+    )
+  }
+}
+
+/// As for [`Decimal`], with [`RoundingMode`]s applied to the signed result. Even roots of
+/// negative values fail with [`Error::Negative`].
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> SignedDecimal<INT_DIGITS, FRAC_DIGITS> {
+  pub fn pow_rounded(self, exp: u32, mode: RoundingMode) -> Result<Self> {
+    let negative = self.0 < 0 && !exp.is_multiple_of(2);
+    let units = pow_units(self.0.unsigned_abs() as u128, FRAC_DIGITS, exp, mode, negative);
+    Self::signed(units, negative)
+  }
+
+  pub fn checked_pow(self, exp: u32) -> Option<Self> {
+    self.pow_rounded(exp, RoundingMode::Down).ok()
+  }
+
+  pub fn sqrt(self, mode: RoundingMode) -> Result<Self> {
+    if self.0 < 0 {
+      return Err(Error::Negative {});
+    }
+    Self::signed(sqrt_units(self.0 as u128, FRAC_DIGITS, mode), false)
+  }
+
+  pub fn nth_root(self, n: u32, mode: RoundingMode) -> Result<Self> {
+    if n == 0 {
+      return Err(Error::DivisionByZero {});
+    }
+    let negative = self.0 < 0;
+    if negative && n.is_multiple_of(2) {
+      return Err(Error::Negative {});
+    }
+    Self::signed(
+      root_units(self.0.unsigned_abs() as u128, FRAC_DIGITS, n, mode, negative),
+      negative,
+    )
+  }
+
+  fn signed(units: Option<u128>, negative: bool) -> Result<Self> {
+    let units = units.and_then(|units| i128::try_from(units).ok()).ok_or(Error::Overflow {})?;
+    Self::checked(if negative { -units } else { units })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{dec, SafeDecimal, SignedSafeDecimal};
+
+  const MODES: [RoundingMode; 7] = [
+    RoundingMode::HalfEven,
+    RoundingMode::HalfUp,
+    RoundingMode::HalfDown,
+    RoundingMode::Up,
+    RoundingMode::Down,
+    RoundingMode::Ceiling,
+    RoundingMode::Floor,
+  ];
+
+  type Small = Decimal<2, 2>;
+
+  /// Units of `(units / 100)^exp`, from the exact `u128` power.
+  fn reference_pow(units: u128, exp: u32, mode: RoundingMode) -> Option<u128> {
+    if exp == 0 {
+      return Some(100);
+    }
+    divide(units.checked_pow(exp)?, 100u128.pow(exp - 1), mode, false)
+  }
+
+  /// Units of the `n`th root of `units / 100`, by searching with exact `u128` powers.
+  fn reference_root(units: u128, n: u32, mode: RoundingMode) -> u128 {
+    let radicand = units * 100u128.pow(n - 1);
+    let mut root = (radicand as f64).powf(1.0 / n as f64) as u128;
+    while root.pow(n) > radicand {
+      root -= 1;
+    }
+    while (root + 1).pow(n) <= radicand {
+      root += 1;
+    }
+    if root.pow(n) == radicand {
+      return root;
+    }
+    // Twice the midpoint against twice the root: (2 * root + 1)^n vs radicand * 2^n.
+    let remainder = if (2 * root + 1).pow(n) < radicand << n { 3 } else { 1 };
+    root + mode.away_from_zero(!root.is_multiple_of(2), remainder, 4, false) as u128
+  }
+
+  #[test]
+  fn test_natural() {
+    let big = Natural::from_u128(u128::MAX);
+    assert_eq!(big.mul(&big).shr(128, false).to_u128(), Some(u128::MAX - 1));
+    assert_eq!(big.shl(3).shr(3, false), big);
+    assert_eq!(big.shl(64).bits(), 192);
+    assert_eq!(Natural::from_u128(5).shr(1, true).to_u128(), Some(3));
+    assert_eq!(Natural::from_u128(4).shr(1, true).to_u128(), Some(2));
+    assert_eq!(big.clone().add_one().to_u128(), None);
+    assert_eq!(big.shl(70).div_rem(7).0.shr(70, false).to_u128(), Some(u128::MAX / 7));
+    assert!(big.shl(1) > big && Natural::from_u128(2) < Natural::from_u128(3));
+    assert!(
+      Natural::from_u128(1 << 70).any_below(71) && !Natural::from_u128(1 << 70).any_below(70)
+    );
+  }
+
+  #[test]
+  fn test_pow_exhaustive() {
+    for units in 0..=Small::MAX.to_units() {
+      let value = Small::from_units(units);
+      for exp in 0..=5 {
+        for mode in MODES {
+          let expected = reference_pow(units as u128, exp, mode)
+            .filter(|&units| units <= Small::MAX.to_units() as u128)
+            .map(|units| Small::from_units(units as u64));
+          assert_eq!(value.pow_rounded(exp, mode).ok(), expected, "{value}^{exp} {mode:?}");
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_root_exhaustive() {
+    for units in 0..=Small::MAX.to_units() {
+      let value = Small::from_units(units);
+      for n in 1..=5 {
+        for mode in MODES {
+          let expected = Small::from_units(reference_root(units as u128, n, mode) as u64);
+          assert_eq!(value.nth_root(n, mode).unwrap(), expected, "{value} root {n} {mode:?}");
+        }
+      }
+      for mode in MODES {
+        assert_eq!(value.sqrt(mode).unwrap(), value.nth_root(2, mode).unwrap(), "sqrt {value}");
+      }
+    }
+  }
+
+  #[test]
+  fn test_bracketing_exhaustive() {
+    // Cases off every boundary, which `pow_units` and `compare_pow` settle exactly in `u128`.
+    for units in 1..=Small::MAX.to_units() as u128 {
+      let (numerator, digits) = reduce(units, 2);
+      for exp in (1..=4).filter(|&exp| digits * exp > 3) {
+        for mode in MODES {
+          let bracketed = bracket_pow(numerator, digits, exp, 2, mode, false);
+          assert_eq!(bracketed, reference_pow(units, exp, mode), "{units}^{exp} {mode:?}");
+        }
+        let radicand = units.pow(exp) / 100u128.pow(exp - 1);
+        for radicand in [radicand.max(1), radicand + 1] {
+          let expected = (units.pow(exp)).cmp(&(radicand * 100u128.pow(exp - 1)));
+          let compared = bracket_compare(numerator, digits, exp, radicand, 2);
+          assert_eq!(compared, expected, "{units}^{exp} vs {radicand}");
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_pow() {
+    assert_eq!(dec!(1.5).checked_pow(2), Some(dec!(2.25)));
+    assert_eq!(dec!(0.001).checked_pow(2), Some(dec!(0.000001)));
+    assert_eq!(dec!(0.001).checked_pow(3), Some(dec!(0)));
+    assert_eq!(dec!(0.001).pow_rounded(3, RoundingMode::Up).unwrap(), dec!(0.000001));
+    assert_eq!(dec!(0.0015).pow_rounded(2, RoundingMode::HalfEven).unwrap(), dec!(0.000002));
+    assert_eq!(dec!(0.0025).pow_rounded(2, RoundingMode::HalfEven).unwrap(), dec!(0.000006));
+    assert_eq!(dec!(0.0025).pow_rounded(2, RoundingMode::HalfDown).unwrap(), dec!(0.000006));
+    assert_eq!(dec!(0.0035).pow_rounded(2, RoundingMode::HalfDown).unwrap(), dec!(0.000012));
+    assert_eq!(dec!(1.000001).checked_pow(1_000_000), Some(dec!(2.718280)));
+    assert_eq!(dec!(1.000001).pow_rounded(1_000_000, RoundingMode::Up).unwrap(), dec!(2.718281));
+    assert_eq!(dec!(1.000001).checked_pow(20_723_276), Some(dec!(999999801.422516)));
+    assert_eq!(dec!(1.000001).checked_pow(20_723_277), None);
+    assert_eq!(dec!(0.999999).checked_pow(u32::MAX), Some(dec!(0)));
+    assert_eq!(dec!(1).checked_pow(u32::MAX), Some(dec!(1)));
+    assert_eq!(dec!(0.1).pow_rounded(100_000, RoundingMode::Up).unwrap(), dec!(0.000001));
+    assert_eq!(dec!(0.1).pow_rounded(1_000_000, RoundingMode::Up).unwrap(), dec!(0.000001));
+    assert_eq!(dec!(0.1).pow_rounded(1_000_000, RoundingMode::HalfUp).unwrap(), dec!(0));
+    assert_eq!(
+      dec!(0.000001).pow_rounded(u32::MAX, RoundingMode::Ceiling).unwrap(),
+      dec!(0.000001)
+    );
+    assert_eq!(dec!(0.999999).pow_rounded(u32::MAX, RoundingMode::Up).unwrap(), dec!(0.000001));
+    assert_eq!(dec!(0).checked_pow(0), Some(dec!(1)));
+    assert_eq!(dec!(2).checked_pow(29), Some(dec!(536870912)));
+    assert_eq!(dec!(2).checked_pow(30), None);
+    assert!(matches!(dec!(10).pow_rounded(9, RoundingMode::Down), Err(Error::Overflow {})));
+    assert!(matches!(
+      SafeDecimal::MAX.pow_rounded(u32::MAX, RoundingMode::Down),
+      Err(Error::Overflow {})
+    ));
+  }
+
+  #[test]
+  fn test_roots() {
+    assert_eq!(dec!(2).sqrt(RoundingMode::HalfEven).unwrap(), dec!(1.414214));
+    assert_eq!(dec!(2).sqrt(RoundingMode::Down).unwrap(), dec!(1.414213));
+    assert_eq!(dec!(6.25).sqrt(RoundingMode::Up).unwrap(), dec!(2.5));
+    assert_eq!(dec!(0.000001).sqrt(RoundingMode::HalfEven).unwrap(), dec!(0.001));
+    assert_eq!(SafeDecimal::MAX.sqrt(RoundingMode::HalfEven).unwrap(), dec!(31622.776602));
+    assert_eq!(dec!(27).nth_root(3, RoundingMode::Down).unwrap(), dec!(3));
+    assert_eq!(dec!(2).nth_root(3, RoundingMode::HalfEven).unwrap(), dec!(1.259921));
+    assert_eq!(dec!(2).nth_root(12, RoundingMode::HalfEven).unwrap(), dec!(1.059463));
+    assert_eq!(dec!(2).nth_root(u32::MAX, RoundingMode::HalfEven).unwrap(), dec!(1));
+    assert_eq!(dec!(2).nth_root(u32::MAX, RoundingMode::Up).unwrap(), dec!(1.000001));
+    assert_eq!(dec!(0.5).nth_root(u32::MAX, RoundingMode::Down).unwrap(), dec!(0.999999));
+    assert_eq!(dec!(0.000001).nth_root(7, RoundingMode::HalfEven).unwrap(), dec!(0.13895));
+    assert_eq!(SafeDecimal::MAX.nth_root(5, RoundingMode::HalfEven).unwrap(), dec!(63.095734));
+    assert!(matches!(dec!(2).nth_root(0, RoundingMode::Down), Err(Error::DivisionByZero {})));
+    assert!(matches!(dec!(0.99, Decimal<0, 2>).sqrt(RoundingMode::Up), Err(Error::Overflow {})));
+  }
+
+  #[test]
+  fn test_signed() {
+    let value = dec!(-1.5, SignedSafeDecimal);
+    assert_eq!(value.checked_pow(2), Some(dec!(2.25, SignedSafeDecimal)));
+    assert_eq!(value.checked_pow(3), Some(dec!(-3.375, SignedSafeDecimal)));
+    let third = dec!(-0.001, SignedSafeDecimal);
+    assert_eq!(
+      third.pow_rounded(3, RoundingMode::Floor).unwrap(),
+      dec!(-0.000001, SignedSafeDecimal)
+    );
+    assert_eq!(third.pow_rounded(3, RoundingMode::Ceiling).unwrap(), dec!(0, SignedSafeDecimal));
+    let tiny = dec!(-0.000001, SignedSafeDecimal);
+    assert_eq!(tiny.pow_rounded(u32::MAX, RoundingMode::Floor).unwrap(), tiny);
+    assert_eq!(
+      tiny.pow_rounded(u32::MAX, RoundingMode::Ceiling).unwrap(),
+      dec!(0, SignedSafeDecimal)
+    );
+    assert_eq!(tiny.pow_rounded(u32::MAX - 1, RoundingMode::Ceiling).unwrap(), tiny.abs());
+    assert_eq!(
+      tiny.pow_rounded(u32::MAX - 1, RoundingMode::Floor).unwrap(),
+      dec!(0, SignedSafeDecimal)
+    );
+    assert_eq!(
+      dec!(-0.1, SignedSafeDecimal).pow_rounded(1_000_001, RoundingMode::Floor).unwrap(),
+      tiny
+    );
+    assert_eq!(
+      dec!(-2, SignedSafeDecimal).nth_root(3, RoundingMode::Floor).unwrap(),
+      dec!(-1.259922, SignedSafeDecimal)
+    );
+    assert_eq!(
+      dec!(-8, SignedSafeDecimal).nth_root(3, RoundingMode::Floor).unwrap(),
+      dec!(-2, SignedSafeDecimal)
+    );
+    assert!(matches!(
+      dec!(-2, SignedSafeDecimal).nth_root(2, RoundingMode::Down),
+      Err(Error::Negative {})
+    ));
+    assert!(matches!(
+      dec!(-2, SignedSafeDecimal).sqrt(RoundingMode::Down),
+      Err(Error::Negative {})
+    ));
+    assert!(dec!(-2, SignedSafeDecimal).checked_pow(30).is_none());
+  }
+}
@@ -0,0 +1,187 @@
+//! Rounding to multiples of a step, such as a price tick, or to fewer decimal places.
+
+use crate::decimal::Decimal;
+use crate::error::{Error, Result};
+use crate::rounding::RoundingMode;
+use crate::signed::SignedDecimal;
+
+/// Units in one step of `decimal_places` digits, or `None` when that is at least the full
+/// precision of `frac_digits`.
+fn dp_step(frac_digits: u32, decimal_places: u32) -> Option<i128> {
+  (decimal_places < frac_digits).then(|| 10i128.pow(frac_digits - decimal_places))
+}
+
+/// A zero step fails with [`Error::DivisionByZero`], and results past [`Decimal::MAX`] with
+/// [`Error::Overflow`].
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Decimal<INT_DIGITS, FRAC_DIGITS> {
+  /// Rounds to a multiple of `step` by `mode`.
+  ///
+  /// ```
+  /// use perfect_decimal::{dec, RoundingMode};
+  ///
+  /// assert_eq!(dec!(1.23).round_to_step(dec!(0.05), RoundingMode::HalfEven).unwrap(), dec!(1.25));
+  /// ```
+  pub fn round_to_step(self, step: Self, mode: RoundingMode) -> Result<Self> {
+    if step.0 == 0 {
+      return Err(Error::DivisionByZero {});
+    }
+    let step = step.0 as i128;
+    Self::checked((mode.divide(self.0 as i128, step) * step) as u128)
+  }
+
+  /// Whether `self` is a whole number of `step`s. Only zero is a multiple of zero.
+  pub fn is_multiple_of(self, step: Self) -> bool {
+    self.0.is_multiple_of(step.0)
+  }
+
+  /// Largest multiple of `step` not above `self`.
+  pub fn floor_to(self, step: Self) -> Result<Self> {
+    self.round_to_step(step, RoundingMode::Floor)
+  }
+
+  /// Smallest multiple of `step` not below `self`.
+  pub fn ceil_to(self, step: Self) -> Result<Self> {
+    self.round_to_step(step, RoundingMode::Ceiling)
+  }
+
+  /// Rounds half to even to `decimal_places` fractional digits, leaving `self` as is when
+  /// that is at least `FRAC_DIGITS`.
+  pub fn round_dp(self, decimal_places: u32) -> Result<Self> {
+    self.round_dp_with(decimal_places, RoundingMode::HalfEven)
+  }
+
+  /// Drops the fractional digits past `decimal_places`, which never overflows.
+  pub fn trunc_dp(self, decimal_places: u32) -> Result<Self> {
+    self.round_dp_with(decimal_places, RoundingMode::Down)
+  }
+
+  fn round_dp_with(self, decimal_places: u32, mode: RoundingMode) -> Result<Self> {
+    match dp_step(FRAC_DIGITS, decimal_places) {
+      Some(step) => Self::checked((mode.divide(self.0 as i128, step) * step) as u128),
+      None => Ok(self),
+    }
+  }
+}
+
+/// As for [`Decimal`], with [`RoundingMode`]s applied to the signed value, so
+/// [`SignedDecimal::floor_to`] moves negative values away from zero. A negative step has the
+/// same multiples as its absolute value and is used as such.
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> SignedDecimal<INT_DIGITS, FRAC_DIGITS> {
+  pub fn round_to_step(self, step: Self, mode: RoundingMode) -> Result<Self> {
+    if step.0 == 0 {
+      return Err(Error::DivisionByZero {});
+    }
+    let step = step.0.unsigned_abs() as i128;
+    Self::checked(mode.divide(self.0 as i128, step) * step)
+  }
+
+  pub fn is_multiple_of(self, step: Self) -> bool {
+    self.0.unsigned_abs().is_multiple_of(step.0.unsigned_abs())
+  }
+
+  pub fn floor_to(self, step: Self) -> Result<Self> {
+    self.round_to_step(step, RoundingMode::Floor)
+  }
+
+  pub fn ceil_to(self, step: Self) -> Result<Self> {
+    self.round_to_step(step, RoundingMode::Ceiling)
+  }
+
+  pub fn round_dp(self, decimal_places: u32) -> Result<Self> {
+    self.round_dp_with(decimal_places, RoundingMode::HalfEven)
+  }
+
+  pub fn trunc_dp(self, decimal_places: u32) -> Result<Self> {
+    self.round_dp_with(decimal_places, RoundingMode::Down)
+  }
+
+  fn round_dp_with(self, decimal_places: u32, mode: RoundingMode) -> Result<Self> {
+    match dp_step(FRAC_DIGITS, decimal_places) {
+      Some(step) => Self::checked(mode.divide(self.0 as i128, step) * step),
+      None => Ok(self),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{dec, SafeDecimal, SignedSafeDecimal};
+
+  #[test]
+  fn test_round_to_step() {
+    let tick = dec!(0.05);
+    assert_eq!(dec!(1.22).round_to_step(tick, RoundingMode::HalfEven).unwrap(), dec!(1.2));
+    assert_eq!(dec!(1.225).round_to_step(tick, RoundingMode::HalfEven).unwrap(), dec!(1.2));
+    assert_eq!(dec!(1.275).round_to_step(tick, RoundingMode::HalfEven).unwrap(), dec!(1.3));
+    assert_eq!(dec!(1.225).round_to_step(tick, RoundingMode::HalfUp).unwrap(), dec!(1.25));
+    assert_eq!(dec!(1.21).floor_to(tick).unwrap(), dec!(1.2));
+    assert_eq!(dec!(1.21).ceil_to(tick).unwrap(), dec!(1.25));
+    assert_eq!(dec!(1.25).ceil_to(tick).unwrap(), dec!(1.25));
+    assert_eq!(dec!(7).floor_to(dec!(0.25)).unwrap(), dec!(7));
+    assert_eq!(dec!(7.3).round_to_step(dec!(3), RoundingMode::HalfEven).unwrap(), dec!(6));
+
+    assert!(matches!(SafeDecimal::MAX.ceil_to(dec!(0.01)), Err(Error::Overflow {})));
+    assert_eq!(SafeDecimal::MAX.floor_to(dec!(0.01)).unwrap(), dec!(999999999.99));
+    assert!(matches!(
+      dec!(1).round_to_step(SafeDecimal::ZERO, RoundingMode::HalfEven),
+      Err(Error::DivisionByZero {})
+    ));
+  }
+
+  #[test]
+  fn test_is_multiple_of() {
+    assert!(dec!(1.25).is_multiple_of(dec!(0.05)));
+    assert!(dec!(0).is_multiple_of(dec!(0.05)));
+    assert!(!dec!(1.26).is_multiple_of(dec!(0.05)));
+    assert!(!dec!(1).is_multiple_of(SafeDecimal::ZERO));
+    assert!(SafeDecimal::ZERO.is_multiple_of(SafeDecimal::ZERO));
+    assert!(dec!(-1.5, SignedSafeDecimal).is_multiple_of(dec!(0.5, SignedSafeDecimal)));
+  }
+
+  #[test]
+  fn test_round_dp() {
+    assert_eq!(dec!(2.345678).round_dp(2).unwrap(), dec!(2.35));
+    assert_eq!(dec!(2.345).round_dp(2).unwrap(), dec!(2.34));
+    assert_eq!(dec!(2.5).round_dp(0).unwrap(), dec!(2));
+    assert_eq!(dec!(3.5).round_dp(0).unwrap(), dec!(4));
+    assert_eq!(dec!(2.345678).trunc_dp(3).unwrap(), dec!(2.345));
+    assert_eq!(dec!(2.345678).round_dp(6).unwrap(), dec!(2.345678));
+    assert_eq!(dec!(2.345678).trunc_dp(9).unwrap(), dec!(2.345678));
+    assert!(matches!(SafeDecimal::MAX.round_dp(0), Err(Error::Overflow {})));
+    assert_eq!(SafeDecimal::MAX.trunc_dp(0).unwrap(), dec!(999999999));
+    assert_eq!(dec!(1.5, Decimal<12, 2>).round_dp(0).unwrap(), dec!(2, Decimal<12, 2>));
+  }
+
+  #[test]
+  fn test_signed() {
+    let tick = dec!(0.25, SignedSafeDecimal);
+    assert_eq!(
+      dec!(-1.1, SignedSafeDecimal).floor_to(tick).unwrap(),
+      dec!(-1.25, SignedSafeDecimal)
+    );
+    assert_eq!(dec!(-1.1, SignedSafeDecimal).ceil_to(tick).unwrap(), dec!(-1, SignedSafeDecimal));
+    assert_eq!(
+      dec!(-1.125, SignedSafeDecimal).round_to_step(tick, RoundingMode::HalfUp).unwrap(),
+      dec!(-1.25, SignedSafeDecimal)
+    );
+    assert_eq!(
+      dec!(-2.345, SignedSafeDecimal).round_dp(2).unwrap(),
+      dec!(-2.34, SignedSafeDecimal)
+    );
+    assert_eq!(
+      dec!(-2.349, SignedSafeDecimal).trunc_dp(2).unwrap(),
+      dec!(-2.34, SignedSafeDecimal)
+    );
+    assert!(matches!(SignedSafeDecimal::MIN.floor_to(tick), Err(Error::Overflow {})));
+    let value = dec!(-1.1, SignedSafeDecimal);
+    assert_eq!(value.floor_to(-tick).unwrap(), value.floor_to(tick).unwrap());
+    assert_eq!(value.ceil_to(-tick).unwrap(), dec!(-1, SignedSafeDecimal));
+    assert!(dec!(-1.25, SignedSafeDecimal).is_multiple_of(-tick));
+    assert!(!value.is_multiple_of(-tick));
+    assert!(matches!(
+      SignedSafeDecimal::MIN.round_to_step(-SignedSafeDecimal::MAX, RoundingMode::HalfEven),
+      Ok(SignedSafeDecimal::MIN)
+    ));
+  }
+}
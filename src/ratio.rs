@@ -0,0 +1,312 @@
+//! Exact fractions for chaining arithmetic without intermediate rounding.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::decimal::Decimal;
+use crate::error::{Error, Result};
+use crate::fixed::FixedPoint;
+use crate::rounding::RoundingMode;
+use crate::signed::SignedDecimal;
+use crate::wide::mul_div;
+
+/// An exact fraction in lowest terms with a positive denominator, for computations like
+/// `a * b / c` that would otherwise truncate after every step. Operations fail with
+/// [`Error::Overflow`] once a numerator or denominator leaves the `i128` range, and only
+/// [`SafeRatio::to_decimal`] rounds.
+///
+/// ```
+/// use perfect_decimal::{dec, RoundingMode, SafeDecimal, SafeRatio};
+///
+/// let (a, b, c) = (dec!(10), dec!(2), dec!(3));
+/// assert_eq!(((a / c).unwrap() * b).unwrap(), dec!(6.666666));
+/// let exact = ((a.to_ratio() * b).unwrap() / c).unwrap();
+/// assert_eq!(exact.to_decimal::<SafeDecimal>(RoundingMode::HalfEven).unwrap(), dec!(6.666667));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SafeRatio {
+  numerator: i128,
+  denominator: i128,
+}
+
+impl SafeRatio {
+  pub const ZERO: Self = Self { numerator: 0, denominator: 1 };
+  pub const ONE: Self = Self { numerator: 1, denominator: 1 };
+
+  /// Reduces `numerator / denominator`, failing with [`Error::DivisionByZero`] for a zero
+  /// denominator.
+  pub fn new(numerator: i128, denominator: i128) -> Result<Self> {
+    if denominator == 0 {
+      return Err(Error::DivisionByZero {});
+    }
+    let divisor = gcd(numerator.unsigned_abs(), denominator.unsigned_abs());
+    let (numerator, denominator) = (numerator / divisor as i128, denominator / divisor as i128);
+    if denominator < 0 {
+      let numerator = numerator.checked_neg().ok_or(Error::Overflow {})?;
+      let denominator = denominator.checked_neg().ok_or(Error::Overflow {})?;
+      return Ok(Self { numerator, denominator });
+    }
+    Ok(Self { numerator, denominator })
+  }
+
+  pub fn numerator(&self) -> i128 {
+    self.numerator
+  }
+
+  /// Always positive.
+  pub fn denominator(&self) -> i128 {
+    self.denominator
+  }
+
+  /// Rounds once to the units of `T` by `mode`, failing with [`Error::Overflow`] outside its
+  /// range and with [`Error::Negative`] for negative results of an unsigned type.
+  pub fn to_decimal<T: FixedPoint>(&self, mode: RoundingMode) -> Result<T> {
+    let negative = self.numerator < 0;
+    let scale = 10u128.pow(T::FRAC_DIGITS);
+    let units =
+      mul_div(self.numerator.unsigned_abs(), scale, self.denominator as u128, mode, negative)
+        .and_then(|units| i128::try_from(units).ok())
+        .ok_or(Error::Overflow {})?;
+    if negative && units != 0 && !T::SIGNED {
+      return Err(Error::Negative {});
+    }
+    let units = if negative { -units } else { units };
+    T::try_from_units(T::Units::try_from(units).map_err(|_| Error::Overflow {})?)
+  }
+
+  fn recip(self) -> Result<Self> {
+    Self::new(self.denominator, self.numerator)
+  }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+  while b != 0 {
+    (a, b) = (b, a % b);
+  }
+  a.max(1)
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Decimal<INT_DIGITS, FRAC_DIGITS> {
+  /// The exact value as a [`SafeRatio`], to start a chain of operations that rounds once at
+  /// the end.
+  pub fn to_ratio(self) -> SafeRatio {
+    self.into()
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> SignedDecimal<INT_DIGITS, FRAC_DIGITS> {
+  pub fn to_ratio(self) -> SafeRatio {
+    self.into()
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> From<Decimal<INT_DIGITS, FRAC_DIGITS>>
+  for SafeRatio
+{
+  fn from(value: Decimal<INT_DIGITS, FRAC_DIGITS>) -> Self {
+    Self::new(value.to_units() as i128, Decimal::<INT_DIGITS, FRAC_DIGITS>::SCALE as i128).unwrap()
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> From<SignedDecimal<INT_DIGITS, FRAC_DIGITS>>
+  for SafeRatio
+{
+  fn from(value: SignedDecimal<INT_DIGITS, FRAC_DIGITS>) -> Self {
+    Self::new(value.to_units() as i128, SignedDecimal::<INT_DIGITS, FRAC_DIGITS>::SCALE as i128)
+      .unwrap()
+  }
+}
+
+impl<T: Into<SafeRatio>> Add<T> for SafeRatio {
+  type Output = Result<Self>;
+
+  fn add(self, rhs: T) -> Self::Output {
+    let rhs = rhs.into();
+    let divisor = gcd(self.denominator as u128, rhs.denominator as u128) as i128;
+    let (left, right) = (self.denominator / divisor, rhs.denominator / divisor);
+    let numerator = self
+      .numerator
+      .checked_mul(right)
+      .zip(rhs.numerator.checked_mul(left))
+      .and_then(|(a, b)| a.checked_add(b));
+    let denominator = self.denominator.checked_mul(right);
+    Self::new(numerator.ok_or(Error::Overflow {})?, denominator.ok_or(Error::Overflow {})?)
+  }
+}
+
+impl<T: Into<SafeRatio>> Sub<T> for SafeRatio {
+  type Output = Result<Self>;
+
+  fn sub(self, rhs: T) -> Self::Output {
+    let rhs = rhs.into();
+    Add::add(
+      self,
+      Self { numerator: rhs.numerator.checked_neg().ok_or(Error::Overflow {})?, ..rhs },
+    )
+  }
+}
+
+impl<T: Into<SafeRatio>> Mul<T> for SafeRatio {
+  type Output = Result<Self>;
+
+  fn mul(self, rhs: T) -> Self::Output {
+    let rhs = rhs.into();
+    // Cancelling crosswise first keeps the products small and the result in lowest terms.
+    let first = gcd(self.numerator.unsigned_abs(), rhs.denominator as u128) as i128;
+    let second = gcd(rhs.numerator.unsigned_abs(), self.denominator as u128) as i128;
+    let numerator = (self.numerator / first).checked_mul(rhs.numerator / second);
+    let denominator = (self.denominator / second).checked_mul(rhs.denominator / first);
+    Ok(Self {
+      numerator: numerator.ok_or(Error::Overflow {})?,
+      denominator: denominator.ok_or(Error::Overflow {})?,
+    })
+  }
+}
+
+/// Fails with [`Error::DivisionByZero`] for a zero divisor.
+impl<T: Into<SafeRatio>> Div<T> for SafeRatio {
+  type Output = Result<Self>;
+
+  fn div(self, rhs: T) -> Self::Output {
+    Mul::mul(self, rhs.into().recip()?)
+  }
+}
+
+impl Neg for SafeRatio {
+  type Output = Result<Self>;
+
+  fn neg(self) -> Self::Output {
+    Ok(Self { numerator: self.numerator.checked_neg().ok_or(Error::Overflow {})?, ..self })
+  }
+}
+
+/// Writes `numerator/denominator`, or just the numerator for whole numbers.
+impl Display for SafeRatio {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    match self.denominator {
+      1 => write!(f, "{}", self.numerator),
+      denominator => write!(f, "{}/{}", self.numerator, denominator),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{dec, SafeDecimal, SignedSafeDecimal};
+
+  fn ratio(numerator: i128, denominator: i128) -> SafeRatio {
+    SafeRatio::new(numerator, denominator).unwrap()
+  }
+
+  #[test]
+  fn test_new() {
+    assert_eq!(ratio(6, -4), ratio(-3, 2));
+    assert_eq!(ratio(-6, -4).to_string(), "3/2");
+    assert_eq!(ratio(0, -5), SafeRatio::ZERO);
+    assert_eq!(ratio(7, 7), SafeRatio::ONE);
+    assert_eq!(SafeRatio::from(dec!(2.5)), ratio(5, 2));
+    assert_eq!(SafeRatio::from(dec!(-0.000125, SignedSafeDecimal)), ratio(-1, 8000));
+    assert!(matches!(SafeRatio::new(1, 0), Err(Error::DivisionByZero {})));
+    assert!(matches!(SafeRatio::new(1, i128::MIN), Err(Error::Overflow {})));
+  }
+
+  #[test]
+  fn test_arithmetic() {
+    assert_eq!((ratio(1, 6) + ratio(1, 3)).unwrap(), ratio(1, 2));
+    assert_eq!((ratio(1, 6) - ratio(1, 3)).unwrap(), ratio(-1, 6));
+    assert_eq!((ratio(2, 3) * ratio(9, 4)).unwrap(), ratio(3, 2));
+    assert_eq!((ratio(2, 3) / ratio(-4, 9)).unwrap(), ratio(-3, 2));
+    assert_eq!((ratio(1, 3) + dec!(0.5)).unwrap(), ratio(5, 6));
+    assert_eq!((ratio(1, 3) * dec!(-3, SignedSafeDecimal)).unwrap(), ratio(-1, 1));
+    assert_eq!((-ratio(1, 3)).unwrap(), ratio(-1, 3));
+    assert!(matches!(ratio(1, 3) / SafeRatio::ZERO, Err(Error::DivisionByZero {})));
+    assert!(matches!(ratio(i128::MAX, 1) + SafeRatio::ONE, Err(Error::Overflow {})));
+    assert!(matches!(ratio(1, i128::MAX) * ratio(1, 2), Err(Error::Overflow {})));
+    assert!(matches!(-ratio(i128::MIN, 1), Err(Error::Overflow {})));
+  }
+
+  #[test]
+  fn test_to_decimal() {
+    let third = ratio(1, 3);
+    assert_eq!(third.to_decimal::<SafeDecimal>(RoundingMode::HalfEven).unwrap(), dec!(0.333333));
+    assert_eq!(third.to_decimal::<SafeDecimal>(RoundingMode::Up).unwrap(), dec!(0.333334));
+    assert_eq!(
+      (-third).unwrap().to_decimal::<SignedSafeDecimal>(RoundingMode::Floor).unwrap(),
+      dec!(-0.333334, SignedSafeDecimal)
+    );
+    assert_eq!(
+      ratio(-1, 10_000_000).to_decimal::<SafeDecimal>(RoundingMode::HalfEven).unwrap(),
+      dec!(0)
+    );
+    assert!(matches!(
+      ratio(-1, 3).to_decimal::<SafeDecimal>(RoundingMode::HalfEven),
+      Err(Error::Negative {})
+    ));
+    assert!(matches!(
+      ratio(1_000_000_000, 1).to_decimal::<SafeDecimal>(RoundingMode::HalfEven),
+      Err(Error::Overflow {})
+    ));
+    assert!(matches!(
+      ratio(i128::MAX, 1).to_decimal::<SafeDecimal>(RoundingMode::HalfEven),
+      Err(Error::Overflow {})
+    ));
+    assert_eq!(
+      ratio(i128::MAX, i128::MAX - 1).to_decimal::<SafeDecimal>(RoundingMode::Up).unwrap(),
+      dec!(1.000001)
+    );
+    assert_eq!(
+      ratio(9_999_999_999_999_995, 10_000_000)
+        .to_decimal::<SafeDecimal>(RoundingMode::HalfDown)
+        .unwrap(),
+      SafeDecimal::MAX
+    );
+  }
+
+  #[test]
+  fn test_to_ratio_chain() {
+    // Pro-rating 1000 over 31 days for 7 days, then adding a third, rounds once at the end.
+    let (amount, days, period) = (dec!(1000), dec!(7), dec!(31));
+    let third = (SafeDecimal::ONE.to_ratio() / dec!(3)).unwrap();
+    let prorated = (((amount.to_ratio() * days).unwrap() / period).unwrap() + third).unwrap();
+    assert_eq!(prorated, ratio(21_031, 93));
+    assert_eq!(
+      prorated.to_decimal::<SafeDecimal>(RoundingMode::HalfEven).unwrap(),
+      dec!(226.139785)
+    );
+    assert_eq!(((amount / period).unwrap() * days).unwrap(), dec!(225.806448));
+
+    let loss = dec!(-12.5, SignedSafeDecimal).to_ratio();
+    let share =
+      ((loss * dec!(2, SignedSafeDecimal)).unwrap() / dec!(3, SignedSafeDecimal)).unwrap();
+    assert_eq!(
+      share.to_decimal::<SignedSafeDecimal>(RoundingMode::Floor).unwrap(),
+      dec!(-8.333334, SignedSafeDecimal)
+    );
+  }
+
+  #[test]
+  fn test_chained_matches_single_rounding() {
+    // `a * b / c` rounded once agrees with rounding the exact quotient of the units.
+    let values = [dec!(0.000001), dec!(1), dec!(3), dec!(7.25), dec!(12345.678901), dec!(999999)];
+    for a in values {
+      for b in values {
+        for c in values {
+          let ratio = ((SafeRatio::from(a) * b).unwrap() / c).unwrap();
+          let units = a.to_units() as i128 * b.to_units() as i128;
+          let expected = RoundingMode::HalfEven.divide(units, c.to_units() as i128);
+          let result = ratio.to_decimal::<SafeDecimal>(RoundingMode::HalfEven);
+          if expected <= SafeDecimal::MAX.to_units() as i128 {
+            assert_eq!(
+              result.unwrap(),
+              SafeDecimal::from_units(expected as u64),
+              "{a} * {b} / {c}"
+            );
+          } else {
+            assert!(matches!(result, Err(Error::Overflow {})), "{a} * {b} / {c}");
+          }
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,107 @@
+/// How to round a result that falls between two representable values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+  /// To the nearest value, ties to the even neighbour (banker's rounding).
+  HalfEven,
+  /// To the nearest value, ties away from zero.
+  HalfUp,
+  /// To the nearest value, ties towards zero.
+  HalfDown,
+  /// Away from zero.
+  Up,
+  /// Towards zero, i.e. truncation.
+  Down,
+  /// Towards positive infinity.
+  Ceiling,
+  /// Towards negative infinity.
+  Floor,
+}
+
+impl RoundingMode {
+  /// Divides `numerator` by a non-zero `denominator`, rounding the quotient by this mode.
+  pub(crate) fn divide(self, numerator: i128, denominator: i128) -> i128 {
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if remainder == 0 {
+      return quotient;
+    }
+    let negative = (numerator < 0) != (denominator < 0);
+    let odd = quotient % 2 != 0;
+    match (
+      self.away_from_zero(odd, remainder.unsigned_abs(), denominator.unsigned_abs(), negative),
+      negative,
+    ) {
+      (false, _) => quotient,
+      (true, false) => quotient + 1,
+      (true, true) => quotient - 1,
+    }
+  }
+
+  /// Whether a truncated quotient, `odd` or not, with a non-zero `remainder` of `divisor`
+  /// left over moves one away from zero. `negative` is the sign of the exact quotient.
+  pub(crate) fn away_from_zero(
+    self,
+    odd: bool,
+    remainder: u128,
+    divisor: u128,
+    negative: bool,
+  ) -> bool {
+    match self {
+      RoundingMode::Up => true,
+      RoundingMode::Down => false,
+      RoundingMode::Ceiling => !negative,
+      RoundingMode::Floor => negative,
+      RoundingMode::HalfEven | RoundingMode::HalfUp | RoundingMode::HalfDown => {
+        // Compares twice the remainder to the divisor without overflowing.
+        match remainder.cmp(&(divisor - remainder)) {
+          std::cmp::Ordering::Less => false,
+          std::cmp::Ordering::Greater => true,
+          std::cmp::Ordering::Equal => match self {
+            RoundingMode::HalfUp => true,
+            RoundingMode::HalfDown => false,
+            _ => odd,
+          },
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const MODES: [RoundingMode; 7] = [
+    RoundingMode::HalfEven,
+    RoundingMode::HalfUp,
+    RoundingMode::HalfDown,
+    RoundingMode::Up,
+    RoundingMode::Down,
+    RoundingMode::Ceiling,
+    RoundingMode::Floor,
+  ];
+
+  #[test]
+  fn test_divide_table() {
+    // Tenths of the inputs 5.5, 2.5, 1.6, 1.1, 1.0, -1.0, -1.1, -1.6, -2.5, -5.5 against the
+    // expected results per mode, in the order of `MODES`.
+    let table: [(i128, [i128; 7]); 10] = [
+      (55, [6, 6, 5, 6, 5, 6, 5]),
+      (25, [2, 3, 2, 3, 2, 3, 2]),
+      (16, [2, 2, 2, 2, 1, 2, 1]),
+      (11, [1, 1, 1, 2, 1, 2, 1]),
+      (10, [1, 1, 1, 1, 1, 1, 1]),
+      (-10, [-1, -1, -1, -1, -1, -1, -1]),
+      (-11, [-1, -1, -1, -2, -1, -1, -2]),
+      (-16, [-2, -2, -2, -2, -1, -1, -2]),
+      (-25, [-2, -3, -2, -3, -2, -2, -3]),
+      (-55, [-6, -6, -5, -6, -5, -5, -6]),
+    ];
+    for (tenths, expected) in table {
+      for (mode, expected) in MODES.into_iter().zip(expected) {
+        assert_eq!(mode.divide(tenths, 10), expected, "{mode:?} of {tenths}/10");
+        assert_eq!(mode.divide(-tenths, -10), expected, "{mode:?} of -{tenths}/-10");
+      }
+    }
+  }
+}
@@ -0,0 +1,995 @@
+//! Serde support for the decimal types.
+//!
+//! The default representation is picked from what the target format reports about itself
+//! through `is_human_readable`, the only thing serde lets a type ask:
+//!
+//! - Human-readable formats receive the exact decimal text as a string, e.g. `"123.45"` in
+//!   JSON, TOML, YAML or CSV.
+//! - Binary formats receive the compact fixed-point integer encoding: the integer count of
+//!   `10^-FRAC_DIGITS` units through `serialize_u64` or `serialize_i64`, so `SafeDecimal`
+//!   `1.5` becomes `1500000`. This is exact in every format, and varint encodings such as
+//!   bincode's and postcard's store typical values in fewer bytes than an f64. Serializers
+//!   don't say whether they are self-describing, so MessagePack and CBOR get the units too;
+//!   fields there use [`as_number`] to write native floats instead.
+//!
+//! Deserializing accepts strings, numbers and serde_json's arbitrary precision numbers in
+//! human-readable formats, and the units in binary ones. Strings must be in the strict form
+//! of `FromStr`, while JSON number text may also have an exponent, as in `1e-05`, or be `-0`.
+//!
+//! Fields pick a different wire form with `#[serde(with = "...")]` and one of the modules
+//! below: [`as_json_number`], [`as_number`], [`as_string`], [`as_micros`] or [`compact`],
+//! each with an `option` submodule for `Option` fields. [`as_json_number`] writes bare JSON
+//! numbers such as `123.45` with serde_json.
+//!
+//! schemars reads `with` as a type, so such fields also name the matching schema marker:
+//!
+//! ```
+//! use perfect_decimal::serde::{as_string, AsString};
+//! use perfect_decimal::SafeDecimal;
+//!
+//! #[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+//! struct Quote {
+//!   #[serde(with = "as_string")]
+//!   #[schemars(with = "AsString<SafeDecimal>")]
+//!   price: SafeDecimal,
+//!   #[serde(with = "as_string::option", default)]
+//!   #[schemars(with = "Option<AsString<SafeDecimal>>")]
+//!   limit: Option<SafeDecimal>,
+//! }
+//! ```
+
+use std::fmt::{Formatter, Result as FmtResult};
+use std::marker::PhantomData;
+
+use schemars::{
+  gen::SchemaGenerator,
+  schema::{InstanceType, Metadata, NumberValidation, Schema, SchemaObject, StringValidation},
+  JsonSchema,
+};
+use serde::{
+  de::{Error as DeserializeError, Visitor},
+  Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::decimal::{rescale, Decimal};
+use crate::error::{Error, Result};
+pub use crate::fixed::FixedPoint;
+use crate::parse::ParseOptions;
+use crate::signed::SignedDecimal;
+
+/// Struct name and field under which serde_json's `arbitrary_precision` feature passes number
+/// text.
+const JSON_NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+/// Generates an `option` submodule applying the enclosing module's representation to
+/// `Option` fields, with `None` as null.
+macro_rules! option_module {
+  () => {
+    /// The same representation for `Option` fields, with `None` written as null.
+    pub mod option {
+      use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+      use crate::fixed::FixedPoint;
+
+      struct Borrowed<'a, T>(&'a T);
+
+      impl<T: FixedPoint> Serialize for Borrowed<'_, T> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer {
+          super::serialize(self.0, serializer)
+        }
+      }
+
+      struct Owned<T>(T);
+
+      impl<'de, T: FixedPoint> Deserialize<'de> for Owned<T> {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de> {
+          super::deserialize(deserializer).map(Owned)
+        }
+      }
+
+      pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+      where
+        T: FixedPoint,
+        S: Serializer,
+      {
+        match value {
+          Some(value) => serializer.serialize_some(&Borrowed(value)),
+          None => serializer.serialize_none(),
+        }
+      }
+
+      pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+      where
+        T: FixedPoint,
+        D: Deserializer<'de>,
+      {
+        Ok(Option::<Owned<T>>::deserialize(deserializer)?.map(|owned| owned.0))
+      }
+    }
+  };
+}
+
+/// The exact decimal text as a bare serde_json number, e.g. `123.45`, written without
+/// allocating through serde_json's `arbitrary_precision` number token, which this crate
+/// enables. Only serde_json, and serializers wrapping it, know the token: other formats get
+/// a one-field struct. Anything the default accepts is accepted when deserializing. Schema:
+/// [`AsNumber`].
+pub mod as_json_number {
+  use std::marker::PhantomData;
+
+  use serde::{ser::SerializeStruct, Deserializer, Serializer};
+
+  use super::{DecimalVisitor, FixedPoint, JSON_NUMBER_TOKEN};
+  use crate::format::MAX_STR_LEN;
+
+  pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    T: FixedPoint,
+    S: Serializer,
+  {
+    let mut number = serializer.serialize_struct(JSON_NUMBER_TOKEN, 1)?;
+    number.serialize_field(JSON_NUMBER_TOKEN, value.to_str_buf(&mut [0; MAX_STR_LEN]))?;
+    number.end()
+  }
+
+  pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+  where
+    T: FixedPoint,
+    D: Deserializer<'de>,
+  {
+    deserializer.deserialize_any(DecimalVisitor(PhantomData))
+  }
+
+  option_module!();
+}
+
+/// An f64 through `serialize_f64` in every format, e.g. a native float in MessagePack, CBOR
+/// or TOML. serde_json writes the shortest text reading back as the same f64, which is the
+/// exact decimal, though tiny values get an exponent such as `1e-6`. Reading back recovers
+/// the exact decimal and rejects floats that do not correspond to one. Strings are still
+/// accepted when deserializing from human-readable formats. Schema: [`AsNumber`].
+pub mod as_number {
+  use std::marker::PhantomData;
+
+  use serde::{Deserializer, Serializer};
+
+  use super::{DecimalVisitor, FixedPoint};
+
+  pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    T: FixedPoint,
+    S: Serializer,
+  {
+    serializer.serialize_f64(value.to_f64())
+  }
+
+  pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+  where
+    T: FixedPoint,
+    D: Deserializer<'de>,
+  {
+    if deserializer.is_human_readable() {
+      deserializer.deserialize_any(DecimalVisitor(PhantomData))
+    } else {
+      deserializer.deserialize_f64(DecimalVisitor(PhantomData))
+    }
+  }
+
+  option_module!();
+}
+
+/// The exact decimal text as a string, e.g. `"123.45"`. Only strings are accepted when
+/// deserializing. Schema: [`AsString`].
+pub mod as_string {
+  use std::marker::PhantomData;
+
+  use serde::{Deserializer, Serializer};
+
+  use super::{DecimalVisitor, FixedPoint};
+  use crate::format::MAX_STR_LEN;
+
+  pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    T: FixedPoint,
+    S: Serializer,
+  {
+    serializer.serialize_str(value.to_str_buf(&mut [0; MAX_STR_LEN]))
+  }
+
+  pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+  where
+    T: FixedPoint,
+    D: Deserializer<'de>,
+  {
+    deserializer.deserialize_str(DecimalVisitor(PhantomData))
+  }
+
+  option_module!();
+}
+
+/// An integer count of micro-units (`10^-6`), so `1.5` becomes `1500000`. Decimals with more
+/// than 6 fractional digits fail to serialize when the extra digits are non-zero. Schema:
+/// [`AsMicros`].
+pub mod as_micros {
+  use serde::{
+    de::Error as DeserializeError, ser::Error as SerializeError, Deserialize, Deserializer,
+    Serialize, Serializer,
+  };
+
+  use super::{rescale_units, FixedPoint, MICRO_DIGITS};
+
+  pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    T: FixedPoint,
+    S: Serializer,
+  {
+    rescale_units::<T>(value.to_units(), T::FRAC_DIGITS, MICRO_DIGITS)
+      .map_err(S::Error::custom)?
+      .serialize(serializer)
+  }
+
+  pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+  where
+    T: FixedPoint,
+    D: Deserializer<'de>,
+  {
+    let micros = T::Units::deserialize(deserializer)?;
+    rescale_units::<T>(micros, MICRO_DIGITS, T::FRAC_DIGITS)
+      .and_then(T::try_from_units)
+      .map_err(D::Error::custom)
+  }
+
+  option_module!();
+}
+
+/// Compact fixed-point integer encoding in every format, as binary formats get by default:
+/// the value is written as its integer count of `10^-FRAC_DIGITS` units, so `SafeDecimal`
+/// `1.5` becomes `1500000` in JSON too. Schema: [`Compact`].
+pub mod compact {
+  use serde::{de::Error as DeserializeError, Deserialize, Deserializer, Serialize, Serializer};
+
+  use super::FixedPoint;
+
+  pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    T: FixedPoint,
+    S: Serializer,
+  {
+    value.to_units().serialize(serializer)
+  }
+
+  pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+  where
+    T: FixedPoint,
+    D: Deserializer<'de>,
+  {
+    T::try_from_units(T::Units::deserialize(deserializer)?).map_err(D::Error::custom)
+  }
+
+  option_module!();
+}
+
+const MICRO_DIGITS: u32 = 6;
+
+/// Moves a signed unit count between fractional digit counts, keeping it within `T::Units`.
+fn rescale_units<T: FixedPoint>(units: T::Units, from: u32, to: u32) -> Result<T::Units> {
+  let units: i128 = units.into();
+  let magnitude = rescale(units.unsigned_abs(), from, to)?;
+  let magnitude = i128::try_from(magnitude).map_err(|_| Error::Overflow {})?;
+  T::Units::try_from(if units < 0 { -magnitude } else { magnitude }).map_err(|_| Error::Overflow {})
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> JsonSchema
+  for Decimal<INT_DIGITS, FRAC_DIGITS>
+{
+  fn schema_name() -> String {
+    match (INT_DIGITS, FRAC_DIGITS) {
+      (9, 6) => "SafeDecimal".to_string(),
+      _ => format!("Decimal_{INT_DIGITS}_{FRAC_DIGITS}"),
+    }
+  }
+
+  fn json_schema(_: &mut SchemaGenerator) -> Schema {
+    string_schema::<Self>()
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> JsonSchema
+  for SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+  fn schema_name() -> String {
+    match (INT_DIGITS, FRAC_DIGITS) {
+      (9, 6) => "SignedSafeDecimal".to_string(),
+      _ => format!("SignedDecimal_{INT_DIGITS}_{FRAC_DIGITS}"),
+    }
+  }
+
+  fn json_schema(_: &mut SchemaGenerator) -> Schema {
+    string_schema::<Self>()
+  }
+}
+
+/// Description shared by the string and number schemas.
+fn describe<T: FixedPoint>(representation: &str) -> Option<Box<Metadata>> {
+  let sign = if T::SIGNED { "Signed decimal" } else { "Non-negative decimal" };
+  Some(Box::new(Metadata {
+    description: Some(format!(
+      "{sign} with up to {} integer and {} fractional digits, {representation}.",
+      T::INT_DIGITS,
+      T::FRAC_DIGITS,
+    )),
+    ..Default::default()
+  }))
+}
+
+/// Format hint shared by the string and number schemas.
+fn format_hint<T: FixedPoint>() -> Option<String> {
+  Some(if T::SIGNED { "signed-safe-decimal" } else { "safe-decimal" }.to_string())
+}
+
+/// The default representation in JSON. Its pattern is generated from the options strings are
+/// parsed with, so it matches the same inputs.
+fn string_schema<T: FixedPoint>() -> Schema {
+  SchemaObject {
+    metadata: describe::<T>("as exact decimal text"),
+    instance_type: Some(InstanceType::String.into()),
+    format: format_hint::<T>(),
+    string: Some(Box::new(StringValidation {
+      pattern: Some(STRING_OPTIONS.pattern(T::SIGNED, T::INT_DIGITS, T::FRAC_DIGITS)),
+      ..Default::default()
+    })),
+    ..Default::default()
+  }
+  .into()
+}
+
+/// Bounds and step of the number representations. The f64 values are the nearest doubles to
+/// the exact limits, which is also what the serialized values are read as.
+fn number_schema<T: FixedPoint>() -> Schema {
+  let scale = 10f64.powi(T::FRAC_DIGITS as i32);
+  let max = (10u64.pow(T::INT_DIGITS + T::FRAC_DIGITS) - 1) as f64 / scale;
+  SchemaObject {
+    metadata: describe::<T>("exactly representable as an IEEE 754 double"),
+    instance_type: Some(InstanceType::Number.into()),
+    format: format_hint::<T>(),
+    number: Some(Box::new(NumberValidation {
+      multiple_of: Some(1.0 / scale),
+      minimum: Some(if T::SIGNED { -max } else { 0.0 }),
+      maximum: Some(max),
+      ..Default::default()
+    })),
+    ..Default::default()
+  }
+  .into()
+}
+
+/// Schema of fields using [`as_number`] or [`as_json_number`], for
+/// `#[schemars(with = "AsNumber<SafeDecimal>")]`.
+pub struct AsNumber<T>(PhantomData<T>);
+
+/// Schema of fields using [`as_string`], for `#[schemars(with = "AsString<SafeDecimal>")]`,
+/// the same as the default's but inlined.
+pub struct AsString<T>(PhantomData<T>);
+
+/// Schema of fields using [`as_micros`], for `#[schemars(with = "AsMicros<SafeDecimal>")]`.
+pub struct AsMicros<T>(PhantomData<T>);
+
+/// Schema of fields using [`compact`], for `#[schemars(with = "Compact<SafeDecimal>")]`.
+pub struct Compact<T>(PhantomData<T>);
+
+impl<T: FixedPoint> JsonSchema for AsNumber<T> {
+  fn is_referenceable() -> bool {
+    false
+  }
+
+  fn schema_name() -> String {
+    "DecimalNumber".to_string()
+  }
+
+  fn json_schema(_: &mut SchemaGenerator) -> Schema {
+    number_schema::<T>()
+  }
+}
+
+impl<T: FixedPoint> JsonSchema for AsString<T> {
+  fn is_referenceable() -> bool {
+    false
+  }
+
+  fn schema_name() -> String {
+    "DecimalString".to_string()
+  }
+
+  fn json_schema(_: &mut SchemaGenerator) -> Schema {
+    string_schema::<T>()
+  }
+}
+
+impl<T: FixedPoint> JsonSchema for AsMicros<T> {
+  fn is_referenceable() -> bool {
+    false
+  }
+
+  fn schema_name() -> String {
+    "DecimalMicros".to_string()
+  }
+
+  fn json_schema(_: &mut SchemaGenerator) -> Schema {
+    let max_units = 10u128.pow(T::INT_DIGITS + T::FRAC_DIGITS) - 1;
+    let max_micros = match MICRO_DIGITS.checked_sub(T::FRAC_DIGITS) {
+      Some(digits) => max_units * 10u128.pow(digits),
+      None => max_units / 10u128.pow(T::FRAC_DIGITS - MICRO_DIGITS),
+    };
+    integer_schema::<T>(max_micros)
+  }
+}
+
+impl<T: FixedPoint> JsonSchema for Compact<T> {
+  fn is_referenceable() -> bool {
+    false
+  }
+
+  fn schema_name() -> String {
+    "DecimalUnits".to_string()
+  }
+
+  fn json_schema(_: &mut SchemaGenerator) -> Schema {
+    integer_schema::<T>(10u128.pow(T::INT_DIGITS + T::FRAC_DIGITS) - 1)
+  }
+}
+
+fn integer_schema<T: FixedPoint>(max: u128) -> Schema {
+  SchemaObject {
+    instance_type: Some(InstanceType::Integer.into()),
+    number: Some(Box::new(NumberValidation {
+      minimum: Some(if T::SIGNED { -(max as f64) } else { 0.0 }),
+      maximum: Some(max as f64),
+      ..Default::default()
+    })),
+    ..Default::default()
+  }
+  .into()
+}
+
+/// Writes the exact text in human-readable formats and the integer units in binary formats.
+fn serialize_decimal<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+  T: FixedPoint,
+  S: Serializer,
+{
+  if serializer.is_human_readable() {
+    as_string::serialize(value, serializer)
+  } else {
+    compact::serialize(value, serializer)
+  }
+}
+
+fn deserialize_decimal<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+  T: FixedPoint,
+  D: Deserializer<'de>,
+{
+  if deserializer.is_human_readable() {
+    deserializer.deserialize_any(DecimalVisitor(PhantomData))
+  } else {
+    compact::deserialize(deserializer)
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Serialize for Decimal<INT_DIGITS, FRAC_DIGITS> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where S: Serializer {
+    serialize_decimal(self, serializer)
+  }
+}
+
+impl<'de, const INT_DIGITS: u32, const FRAC_DIGITS: u32> Deserialize<'de>
+  for Decimal<INT_DIGITS, FRAC_DIGITS>
+{
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where D: Deserializer<'de> {
+    deserialize_decimal(deserializer)
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Serialize
+  for SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where S: Serializer {
+    serialize_decimal(self, serializer)
+  }
+}
+
+impl<'de, const INT_DIGITS: u32, const FRAC_DIGITS: u32> Deserialize<'de>
+  for SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where D: Deserializer<'de> {
+    deserialize_decimal(deserializer)
+  }
+}
+
+/// Options for decimal strings, shared with the string schema pattern.
+const STRING_OPTIONS: ParseOptions = ParseOptions::strict();
+
+/// Options for the text of JSON numbers, which may have an exponent, as in Python's `1e-05`.
+const NUMBER_OPTIONS: ParseOptions = ParseOptions::strict().exponent(true);
+
+/// Accepts decimal strings and numbers, only yielding values that are exactly representable.
+/// Floats go through the exact recovery of `TryFrom<f64>`.
+struct DecimalVisitor<T>(PhantomData<T>);
+
+impl<T: FixedPoint> DecimalVisitor<T> {
+  /// Parses number text, reading JSON's `-0` as zero like f64 `-0.0` is.
+  fn visit_number<E>(text: &str) -> Result<T, E>
+  where E: DeserializeError {
+    let result = T::parse_with(text, NUMBER_OPTIONS).or_else(|error| {
+      match text.strip_prefix('-').map(|magnitude| T::parse_with(magnitude, NUMBER_OPTIONS)) {
+        Some(Ok(zero)) if zero.to_units().into() == 0 => Ok(zero),
+        _ => Err(error),
+      }
+    });
+    result.map_err(E::custom)
+  }
+}
+
+impl<'de, T> Visitor<'de> for DecimalVisitor<T>
+where T: FixedPoint
+{
+  type Value = T;
+
+  fn expecting(&self, f: &mut Formatter) -> FmtResult {
+    write!(f, "an exactly representable decimal number or string")
+  }
+
+  fn visit_str<E>(self, v: &str) -> Result<T, E>
+  where E: DeserializeError {
+    T::parse_with(v, STRING_OPTIONS).map_err(E::custom)
+  }
+
+  fn visit_u64<E>(self, v: u64) -> Result<T, E>
+  where E: DeserializeError {
+    self.visit_str(&v.to_string())
+  }
+
+  fn visit_i64<E>(self, v: i64) -> Result<T, E>
+  where E: DeserializeError {
+    self.visit_str(&v.to_string())
+  }
+
+  fn visit_u128<E>(self, v: u128) -> Result<T, E>
+  where E: DeserializeError {
+    self.visit_str(&v.to_string())
+  }
+
+  fn visit_i128<E>(self, v: i128) -> Result<T, E>
+  where E: DeserializeError {
+    self.visit_str(&v.to_string())
+  }
+
+  fn visit_f64<E>(self, v: f64) -> Result<T, E>
+  where E: DeserializeError {
+    T::try_from(v).map_err(E::custom)
+  }
+
+  /// Arbitrary precision numbers from serde_json.
+  fn visit_map<A>(self, map: A) -> Result<T, A::Error>
+  where A: serde::de::MapAccess<'de> {
+    let number =
+      serde_json::Number::deserialize(serde::de::value::MapAccessDeserializer::new(map))?;
+    Self::visit_number(number.as_str())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr;
+
+  use serde::de::{value::Error as ValueError, IntoDeserializer};
+  use serde::forward_to_deserialize_any;
+  use serde::ser::{Error as SerializeError, Impossible};
+
+  use super::*;
+  use crate::decimal::SafeDecimal;
+  use crate::signed::SignedSafeDecimal;
+
+  /// Primitive written by a test format.
+  #[derive(Debug, PartialEq)]
+  enum Wire {
+    F64(f64),
+    U64(u64),
+    I64(i64),
+    Str(String),
+  }
+
+  /// Minimal format carrying a single primitive, standing in for a binary format or, when
+  /// human-readable, for one like TOML or YAML.
+  struct Format {
+    human_readable: bool,
+  }
+
+  const BINARY: Format = Format { human_readable: false };
+  const TEXT: Format = Format { human_readable: true };
+
+  macro_rules! unsupported {
+    ($($method:ident($($arg:ty),*) -> $ret:ty;)*) => {
+      $(fn $method(self, $(_: $arg),*) -> Result<$ret, ValueError> {
+        Err(<ValueError as SerializeError>::custom(stringify!($method)))
+      })*
+    };
+  }
+
+  impl Serializer for Format {
+    type Ok = Wire;
+    type Error = ValueError;
+    type SerializeSeq = Impossible<Wire, ValueError>;
+    type SerializeTuple = Impossible<Wire, ValueError>;
+    type SerializeTupleStruct = Impossible<Wire, ValueError>;
+    type SerializeTupleVariant = Impossible<Wire, ValueError>;
+    type SerializeMap = Impossible<Wire, ValueError>;
+    type SerializeStruct = Impossible<Wire, ValueError>;
+    type SerializeStructVariant = Impossible<Wire, ValueError>;
+
+    fn is_human_readable(&self) -> bool {
+      self.human_readable
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Wire, ValueError> {
+      Ok(Wire::F64(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Wire, ValueError> {
+      Ok(Wire::U64(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Wire, ValueError> {
+      Ok(Wire::I64(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Wire, ValueError> {
+      Ok(Wire::Str(v.to_string()))
+    }
+
+    unsupported! {
+      serialize_bool(bool) -> Wire;
+      serialize_i8(i8) -> Wire;
+      serialize_i16(i16) -> Wire;
+      serialize_i32(i32) -> Wire;
+      serialize_u8(u8) -> Wire;
+      serialize_u16(u16) -> Wire;
+      serialize_u32(u32) -> Wire;
+      serialize_f32(f32) -> Wire;
+      serialize_char(char) -> Wire;
+      serialize_bytes(&[u8]) -> Wire;
+      serialize_none() -> Wire;
+      serialize_unit() -> Wire;
+      serialize_unit_struct(&'static str) -> Wire;
+      serialize_unit_variant(&'static str, u32, &'static str) -> Wire;
+      serialize_seq(Option<usize>) -> Self::SerializeSeq;
+      serialize_tuple(usize) -> Self::SerializeTuple;
+      serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+      serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+      serialize_map(Option<usize>) -> Self::SerializeMap;
+      serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+      serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+
+    fn serialize_some<T: ?Sized+Serialize>(self, _: &T) -> Result<Wire, ValueError> {
+      Err(<ValueError as SerializeError>::custom("serialize_some"))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized+Serialize>(
+      self,
+      _: &'static str,
+      _: &T,
+    ) -> Result<Wire, ValueError> {
+      Err(<ValueError as SerializeError>::custom("serialize_newtype_struct"))
+    }
+
+    fn serialize_newtype_variant<T: ?Sized+Serialize>(
+      self,
+      _: &'static str,
+      _: u32,
+      _: &'static str,
+      _: &T,
+    ) -> Result<Wire, ValueError> {
+      Err(<ValueError as SerializeError>::custom("serialize_newtype_variant"))
+    }
+  }
+
+  impl<'de> Deserializer<'de> for Wire {
+    type Error = ValueError;
+
+    fn is_human_readable(&self) -> bool {
+      false
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+      match self {
+        Wire::F64(v) => visitor.visit_f64(v),
+        Wire::U64(v) => visitor.visit_u64(v),
+        Wire::I64(v) => visitor.visit_i64(v),
+        Wire::Str(v) => visitor.visit_string(v),
+      }
+    }
+
+    forward_to_deserialize_any! {
+      bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+      option unit unit_struct newtype_struct seq tuple tuple_struct map struct enum identifier
+      ignored_any
+    }
+  }
+
+  #[test]
+  fn test_binary_round_trip() {
+    for (input, units) in [("0", 0), ("0.1", 100_000), ("123.45", 123_450_000)] {
+      let decimal = SafeDecimal::from_str(input).unwrap();
+      let wire = decimal.serialize(BINARY).unwrap();
+      assert_eq!(wire, Wire::U64(units));
+      assert_eq!(SafeDecimal::deserialize(wire).unwrap(), decimal);
+    }
+    let wire = SafeDecimal::MAX.serialize(BINARY).unwrap();
+    assert_eq!(wire, Wire::U64(999_999_999_999_999));
+    assert_eq!(SafeDecimal::deserialize(wire).unwrap(), SafeDecimal::MAX);
+    let signed = SignedSafeDecimal::from_str("-42.5").unwrap();
+    let wire = signed.serialize(BINARY).unwrap();
+    assert_eq!(wire, Wire::I64(-42_500_000));
+    assert_eq!(SignedSafeDecimal::deserialize(wire).unwrap(), signed);
+  }
+
+  #[test]
+  fn test_binary_rejects_invalid_units() {
+    assert!(SafeDecimal::deserialize(Wire::U64(1_000_000_000_000_000)).is_err());
+    assert!(SafeDecimal::deserialize(Wire::I64(-1)).is_err());
+    assert!(SafeDecimal::deserialize(Wire::F64(1.5)).is_err());
+    assert!(SignedSafeDecimal::deserialize(Wire::I64(-1_000_000_000_000_000)).is_err());
+    assert_eq!(SafeDecimal::deserialize(Wire::U64(7)).unwrap().to_string(), "0.000007");
+  }
+
+  #[test]
+  fn test_as_number() {
+    for input in ["0", "0.1", "123.45", "0.000001", "999999999.999999"] {
+      let decimal = SafeDecimal::from_str(input).unwrap();
+      let wire = as_number::serialize(&decimal, BINARY).unwrap();
+      assert_eq!(wire, Wire::F64(input.parse().unwrap()));
+      assert_eq!(as_number::deserialize::<SafeDecimal, _>(wire).unwrap(), decimal);
+    }
+    let signed = SignedSafeDecimal::from_str("-42.5").unwrap();
+    assert_eq!(as_number::serialize(&signed, TEXT).unwrap(), Wire::F64(-42.5));
+    assert!(as_number::deserialize::<SafeDecimal, _>(Wire::F64(0.1234567)).is_err());
+    assert!(as_number::deserialize::<SafeDecimal, _>(Wire::F64(-1.0)).is_err());
+    assert!(as_number::deserialize::<SafeDecimal, _>(Wire::F64(f64::NAN)).is_err());
+    assert_eq!(as_number::deserialize::<SafeDecimal, _>(Wire::U64(7)).unwrap().to_string(), "7");
+  }
+
+  #[test]
+  fn test_human_readable_is_exact_text() {
+    let decimal = SafeDecimal::from_str("100").unwrap();
+    assert_eq!(serde_json::to_string(&decimal).unwrap(), r#""100""#);
+    let decimal: Result<SafeDecimal, ValueError> =
+      SafeDecimal::deserialize("0.5".into_deserializer());
+    assert_eq!(decimal.unwrap().to_string(), "0.5");
+  }
+
+  #[test]
+  fn test_human_readable_formats_get_strings() {
+    let decimal = SafeDecimal::from_str("123.45").unwrap();
+    assert_eq!(decimal.serialize(TEXT).unwrap(), Wire::Str("123.45".to_string()));
+    let signed = SignedSafeDecimal::from_str("-0.5").unwrap();
+    assert_eq!(signed.serialize(TEXT).unwrap(), Wire::Str("-0.5".to_string()));
+
+    let map = std::collections::BTreeMap::from([(decimal, signed)]);
+    assert_eq!(serde_json::to_string(&map).unwrap(), r#"{"123.45":"-0.5"}"#);
+    assert_eq!(serde_json::to_value(&map).unwrap(), serde_json::json!({ "123.45": "-0.5" }));
+  }
+
+  #[test]
+  fn test_as_json_number() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Tick {
+      #[serde(with = "as_json_number")]
+      price: SafeDecimal,
+      #[serde(with = "as_json_number::option")]
+      change: Option<SignedSafeDecimal>,
+    }
+    let tick = Tick {
+      price: SafeDecimal::from_str("0.000001").unwrap(),
+      change: Some(SignedSafeDecimal::from_str("-12.5").unwrap()),
+    };
+    let json = serde_json::to_string(&tick).unwrap();
+    assert_eq!(json, r#"{"price":0.000001,"change":-12.5}"#);
+    assert_eq!(serde_json::from_str::<Tick>(&json).unwrap(), tick);
+    let value = serde_json::to_value(&tick).unwrap();
+    assert_eq!(value["price"].to_string(), "0.000001");
+    assert_eq!(serde_json::from_value::<Tick>(value).unwrap(), tick);
+
+    // Formats other than serde_json see the token as a struct.
+    assert!(as_json_number::serialize(&tick.price, TEXT).is_err());
+  }
+
+  #[test]
+  fn test_json_number_text() {
+    for (json, expected) in
+      [("1e3", "1000"), ("1E2", "100"), ("1.0e0", "1"), ("1e-05", "0.00001"), ("-0", "0")]
+    {
+      let expected = SafeDecimal::from_str(expected).unwrap();
+      assert_eq!(serde_json::from_str::<SafeDecimal>(json).unwrap(), expected, "{json}");
+      let value: serde_json::Value = serde_json::from_str(json).unwrap();
+      assert_eq!(serde_json::from_value::<SafeDecimal>(value).unwrap(), expected, "{json}");
+    }
+    let signed = serde_json::from_str::<SignedSafeDecimal>("-1.5e-3").unwrap();
+    assert_eq!(signed, SignedSafeDecimal::from_str("-0.0015").unwrap());
+    assert_eq!(serde_json::from_str::<SafeDecimal>("-0.0e5").unwrap(), SafeDecimal::ZERO);
+    assert!(serde_json::from_str::<SafeDecimal>("-1e-7").is_err());
+    assert!(serde_json::from_str::<SafeDecimal>("1e-7").is_err());
+
+    // Strings stay strict.
+    assert!(serde_json::from_str::<SafeDecimal>(r#""1e3""#).is_err());
+    assert!(serde_json::from_str::<SafeDecimal>(r#""-0""#).is_err());
+  }
+
+  #[test]
+  fn test_compact() {
+    let decimal = SafeDecimal::from_str("1.5").unwrap();
+    assert_eq!(compact::serialize(&decimal, BINARY).unwrap(), Wire::U64(1_500_000));
+    assert_eq!(compact::deserialize::<SafeDecimal, _>(Wire::U64(1_500_000)).unwrap(), decimal);
+    assert!(compact::deserialize::<SafeDecimal, _>(Wire::U64(1_000_000_000_000_000)).is_err());
+
+    let signed = SignedSafeDecimal::from_str("-1.5").unwrap();
+    assert_eq!(compact::serialize(&signed, BINARY).unwrap(), Wire::I64(-1_500_000));
+    assert_eq!(
+      compact::deserialize::<SignedSafeDecimal, _>(Wire::I64(-1_500_000)).unwrap(),
+      signed
+    );
+
+    #[derive(Serialize, Deserialize)]
+    struct Row {
+      #[serde(with = "compact")]
+      price: SafeDecimal,
+    }
+    let json = serde_json::to_string(&Row { price: decimal }).unwrap();
+    assert_eq!(json, r#"{"price":1500000}"#);
+    assert_eq!(serde_json::from_str::<Row>(&json).unwrap().price, decimal);
+  }
+
+  #[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
+  struct Modes {
+    #[serde(with = "as_json_number")]
+    #[schemars(with = "AsNumber<SafeDecimal>")]
+    json_number: SafeDecimal,
+    #[serde(with = "as_number")]
+    #[schemars(with = "AsNumber<SafeDecimal>")]
+    number: SafeDecimal,
+    #[serde(with = "as_string")]
+    #[schemars(with = "AsString<SafeDecimal>")]
+    string: SafeDecimal,
+    #[serde(with = "as_micros")]
+    #[schemars(with = "AsMicros<SafeDecimal>")]
+    micros: SafeDecimal,
+    #[serde(with = "as_string::option")]
+    #[schemars(with = "Option<AsString<SignedSafeDecimal>>")]
+    maybe_string: Option<SignedSafeDecimal>,
+    #[serde(with = "as_micros::option")]
+    #[schemars(with = "Option<AsMicros<SignedSafeDecimal>>")]
+    maybe_micros: Option<SignedSafeDecimal>,
+  }
+
+  #[test]
+  fn test_representation_modules() {
+    let decimal = SafeDecimal::from_str("1.5").unwrap();
+    let modes = Modes {
+      json_number: decimal,
+      number: decimal,
+      string: decimal,
+      micros: decimal,
+      maybe_string: Some(SignedSafeDecimal::from_str("-2.25").unwrap()),
+      maybe_micros: None,
+    };
+    let json = serde_json::to_string(&modes).unwrap();
+    assert_eq!(
+      json,
+      concat!(
+        r#"{"json_number":1.5,"number":1.5,"string":"1.5","micros":1500000,"#,
+        r#""maybe_string":"-2.25","maybe_micros":null}"#,
+      )
+    );
+    assert_eq!(serde_json::from_str::<Modes>(&json).unwrap(), modes);
+  }
+
+  #[test]
+  fn test_as_string_rejects_numbers() {
+    let result: Result<SafeDecimal, _> =
+      as_string::deserialize(&mut serde_json::Deserializer::from_str("1.5"));
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_parse_error_messages() {
+    let error = serde_json::from_str::<SafeDecimal>("\"1.2x\"").unwrap_err();
+    assert_eq!(error.to_string(), "Invalid digit at byte 3: \"x\" at line 1 column 6");
+    let error = serde_json::from_str::<SafeDecimal>("1.2345678").unwrap_err();
+    assert!(error.to_string().starts_with("Too many fractional digits at byte 8: \"8\""));
+    let error = serde_json::from_str::<SignedSafeDecimal>("\"-1000000000\"").unwrap_err();
+    assert!(error.to_string().starts_with("Integral part too large at byte 1"));
+  }
+
+  #[test]
+  fn test_as_micros_precision() {
+    let wide = Decimal::<7, 8>::from_str("1.00000001").unwrap();
+    assert!(as_micros::serialize(&wide, BINARY).is_err());
+    let wide = Decimal::<7, 8>::from_str("1.5").unwrap();
+    assert_eq!(as_micros::serialize(&wide, BINARY).unwrap(), Wire::U64(1_500_000));
+    let narrow: Decimal<12, 3> = as_micros::deserialize(Wire::U64(1_500_000)).unwrap();
+    assert_eq!(narrow.to_string(), "1.5");
+    assert!(as_micros::deserialize::<Decimal<12, 3>, _>(Wire::U64(1_500_001)).is_err());
+  }
+
+  #[test]
+  fn test_representation_schemas() {
+    let schema = serde_json::to_value(schemars::schema_for!(Modes)).unwrap();
+    let properties = &schema["properties"];
+    assert_eq!(properties["string"]["type"], "string");
+    assert_eq!(
+      properties["string"]["pattern"],
+      r"^(00*|(00*)?[1-9][0-9]{0,8})(\.([0-9][0-9]{0,5}(0)*))?$"
+    );
+    assert_eq!(properties["micros"]["type"], "integer");
+    assert_eq!(properties["micros"]["minimum"], serde_json::json!(0.0));
+    assert_eq!(properties["micros"]["maximum"], serde_json::json!(999999999999999.0));
+    assert_eq!(properties["maybe_string"]["type"], serde_json::json!(["string", "null"]));
+    assert!(properties["maybe_string"]["pattern"].as_str().unwrap().starts_with("^-?"));
+    assert_eq!(properties["maybe_micros"]["minimum"], serde_json::json!(-999999999999999.0));
+  }
+
+  #[test]
+  fn test_default_schema() {
+    let schema = serde_json::to_value(schemars::schema_for!(SafeDecimal)).unwrap();
+    assert_eq!(schema["title"], "SafeDecimal");
+    assert_eq!(schema["type"], "string");
+    assert_eq!(schema["format"], "safe-decimal");
+    assert_eq!(schema["pattern"], r"^(00*|(00*)?[1-9][0-9]{0,8})(\.([0-9][0-9]{0,5}(0)*))?$");
+    assert!(schema["description"].as_str().unwrap().contains("6 fractional digits"));
+
+    let schema = serde_json::to_value(schemars::schema_for!(SignedDecimal<12, 3>)).unwrap();
+    assert_eq!(schema["title"], "SignedDecimal_12_3");
+    assert_eq!(schema["format"], "signed-safe-decimal");
+    assert!(schema["pattern"].as_str().unwrap().starts_with("^-?"));
+  }
+
+  #[test]
+  fn test_number_schema() {
+    let schema = serde_json::to_value(schemars::schema_for!(AsNumber<SafeDecimal>)).unwrap();
+    assert_eq!(schema["title"], "DecimalNumber");
+    assert_eq!(schema["type"], "number");
+    assert_eq!(schema["format"], "safe-decimal");
+    assert_eq!(schema["minimum"], serde_json::json!(0.0));
+    assert_eq!(schema["maximum"], serde_json::json!(999999999.999999));
+    assert_eq!(schema["multipleOf"], serde_json::json!(0.000001));
+    assert!(schema["description"].as_str().unwrap().contains("6 fractional digits"));
+
+    let schema = serde_json::to_value(schemars::schema_for!(AsNumber<SignedDecimal<12, 3>>));
+    let schema = schema.unwrap();
+    assert_eq!(schema["format"], "signed-safe-decimal");
+    assert_eq!(schema["minimum"], serde_json::json!(-999999999999.999));
+    assert_eq!(schema["multipleOf"], serde_json::json!(0.001));
+  }
+
+  #[test]
+  fn test_schema_reference() {
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Invoice {
+      total: SafeDecimal,
+      #[schemars(with = "AsNumber<SafeDecimal>")]
+      fee: SafeDecimal,
+    }
+    let schema = serde_json::to_value(schemars::schema_for!(Invoice)).unwrap();
+    assert_eq!(schema["properties"]["total"]["$ref"], "#/definitions/SafeDecimal");
+    assert_eq!(schema["definitions"]["SafeDecimal"]["type"], "string");
+    assert_eq!(schema["properties"]["fee"]["type"], "number");
+    assert_eq!(schema["properties"]["fee"]["maximum"], serde_json::json!(999999999.999999));
+  }
+}
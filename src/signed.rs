@@ -0,0 +1,643 @@
+use std::fmt::{Display, Formatter, LowerExp, Result as FmtResult, UpperExp};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::str::FromStr;
+
+use crate::decimal::{rescale, Decimal};
+use crate::error::{Error, Result};
+use crate::format::{fmt_exp, fmt_units, format_units, Engineering, Notation, MAX_STR_LEN};
+use crate::locale::{parse_format, Formatted, NumberFormat};
+use crate::parse::{parse, ParseOptions};
+use crate::rounding::RoundingMode;
+
+/// Signed counterpart to [`Decimal`], covering the symmetric range of its unsigned
+/// configuration with the same lossless f64 guarantee.
+///
+/// ```compile_fail
+/// let _ = perfect_decimal::SignedDecimal::<12, 6>::MIN_POSITIVE;
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SignedDecimal<const INT_DIGITS: u32, const FRAC_DIGITS: u32>(pub(crate) i64);
+
+/// Signed counterpart to [`SafeDecimal`](crate::SafeDecimal): `-999,999,999.999999` to
+/// `999,999,999.999999`.
+pub type SignedSafeDecimal = SignedDecimal<9, 6>;
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> SignedDecimal<INT_DIGITS, FRAC_DIGITS> {
+  /// Number of fractional digits.
+  pub const DECIMALS: u32 = FRAC_DIGITS;
+  /// Units per whole number, `10^FRAC_DIGITS`.
+  pub const SCALE: i64 = Decimal::<INT_DIGITS, FRAC_DIGITS>::SCALE as i64;
+  const MAX_UNITS: i64 = Decimal::<INT_DIGITS, FRAC_DIGITS>::MAX_VAL as i64;
+
+  pub const ZERO: Self = {
+    let _ = Self::SCALE;
+    Self(0)
+  };
+  /// Fails to compile for configurations without integer digits.
+  pub const ONE: Self = Self::from_units(Self::SCALE);
+  /// Largest value, all digits nines.
+  pub const MAX: Self = Self(Self::MAX_UNITS);
+  /// Smallest value, the negation of [`SignedDecimal::MAX`].
+  pub const MIN: Self = Self(-Self::MAX_UNITS);
+  /// Smallest positive value, one unit of `10^-FRAC_DIGITS`.
+  pub const MIN_POSITIVE: Self = {
+    let _ = Self::SCALE;
+    Self(1)
+  };
+
+  pub const fn new(negative: bool, integral: u32, fractional: u32) -> Result<Self> {
+    Self::try_from_parts(negative, integral as u64, fractional as u64)
+  }
+
+  /// Like [`SignedDecimal::new`], for configurations whose parts may not fit in a `u32`.
+  pub const fn try_from_parts(negative: bool, integral: u64, fractional: u64) -> Result<Self> {
+    match Decimal::<INT_DIGITS, FRAC_DIGITS>::try_from_parts(integral, fractional) {
+      Ok(magnitude) if negative => Ok(Self(-(magnitude.0 as i64))),
+      Ok(magnitude) => Ok(Self(magnitude.0 as i64)),
+      Err(error) => Err(error),
+    }
+  }
+
+  /// Like [`SignedDecimal::try_from_parts`], but panics when out of range, which is a
+  /// compile error in const contexts.
+  pub const fn from_parts(negative: bool, integral: u64, fractional: u64) -> Self {
+    match Self::try_from_parts(negative, integral, fractional) {
+      Ok(decimal) => decimal,
+      Err(_) => panic!("decimal parts out of range"),
+    }
+  }
+
+  /// Creates a decimal from its signed count of `10^-FRAC_DIGITS` units, failing when
+  /// outside [`SignedDecimal::MIN`] to [`SignedDecimal::MAX`].
+  pub const fn try_from_units(units: i64) -> Result<Self> {
+    if units.unsigned_abs() > Self::MAX_UNITS as u64 {
+      return Err(Error::Overflow {});
+    }
+    Ok(Self(units))
+  }
+
+  /// Like [`SignedDecimal::try_from_units`], but panics when out of range, which is a
+  /// compile error in const contexts.
+  pub const fn from_units(units: i64) -> Self {
+    match Self::try_from_units(units) {
+      Ok(decimal) => decimal,
+      Err(_) => panic!("decimal units out of range"),
+    }
+  }
+
+  /// Signed count of `10^-FRAC_DIGITS` units.
+  pub const fn to_units(self) -> i64 {
+    self.0
+  }
+
+  pub(crate) fn checked(value: i128) -> Result<Self> {
+    if value.unsigned_abs() > Self::MAX_UNITS as u128 {
+      return Err(Error::Overflow {});
+    }
+    Ok(Self(value as i64))
+  }
+
+  /// Integral part of the magnitude, failing to compile like [`Decimal::integral`].
+  pub const fn integral(&self) -> u32 {
+    Decimal::<INT_DIGITS, FRAC_DIGITS>(self.0.unsigned_abs()).integral()
+  }
+
+  /// Fractional part of the magnitude, failing to compile like [`Decimal::fractional`].
+  pub const fn fractional(&self) -> u32 {
+    Decimal::<INT_DIGITS, FRAC_DIGITS>(self.0.unsigned_abs()).fractional()
+  }
+
+  /// Integral and fractional parts of the magnitude, for any configuration.
+  pub const fn to_parts(self) -> (u64, u64) {
+    Decimal::<INT_DIGITS, FRAC_DIGITS>(self.0.unsigned_abs()).to_parts()
+  }
+
+  pub fn is_negative(&self) -> bool {
+    self.0 < 0
+  }
+
+  pub fn is_positive(&self) -> bool {
+    self.0 > 0
+  }
+
+  pub fn abs(&self) -> Self {
+    Self(self.0.abs())
+  }
+
+  /// Magnitude as an unsigned [`Decimal`], which can always hold it.
+  pub fn unsigned_abs(&self) -> Decimal<INT_DIGITS, FRAC_DIGITS> {
+    Decimal(self.0.unsigned_abs())
+  }
+
+  /// Returns `-1`, `0` or `1` depending on the sign of the value. This is a plain integer
+  /// because `1` is out of range when `INT_DIGITS` is zero.
+  pub fn signum(&self) -> i32 {
+    self.0.signum() as i32
+  }
+
+  /// Nearest f64, exact in the same sense as [`Decimal::to_f64`].
+  pub fn to_f64(self) -> f64 {
+    self.0 as f64 / Self::SCALE as f64
+  }
+
+  /// Multiplies, rounding the exact product to `FRAC_DIGITS` digits by `mode`.
+  pub fn mul_rounded(self, rhs: Self, mode: RoundingMode) -> Result<Self> {
+    Self::checked(mode.divide(self.0 as i128 * rhs.0 as i128, Self::SCALE as i128))
+  }
+
+  /// Divides, rounding the exact quotient to `FRAC_DIGITS` digits by `mode`.
+  pub fn div_rounded(self, rhs: Self, mode: RoundingMode) -> Result<Self> {
+    if rhs.0 == 0 {
+      return Err(Error::DivisionByZero {});
+    }
+    Self::checked(mode.divide(self.0 as i128 * Self::SCALE as i128, rhs.0 as i128))
+  }
+
+  /// Computes `self * num / den` from the exact product, rounding once by `mode`, so it only
+  /// fails when the result itself is out of range.
+  pub fn mul_div(self, num: Self, den: Self, mode: RoundingMode) -> Result<Self> {
+    if den.0 == 0 {
+      return Err(Error::DivisionByZero {});
+    }
+    Self::checked(mode.divide(self.0 as i128 * num.0 as i128, den.0 as i128))
+  }
+
+  /// What percentage `self` is of `whole`, rounded by `mode`: `-25` is `-12.5`% of `200`.
+  pub fn as_percent_of(self, whole: Self, mode: RoundingMode) -> Result<Self> {
+    self.fraction_of(whole, 100, mode)
+  }
+
+  /// What `self` is of `whole` in basis points, rounded by `mode`: `-25` is `-1250` basis
+  /// points of `200`.
+  pub fn as_basis_points_of(self, whole: Self, mode: RoundingMode) -> Result<Self> {
+    self.fraction_of(whole, 10_000, mode)
+  }
+
+  /// Takes `percent` percent of `self`, rounded by `mode`: `-12.5`% of `200` is `-25`.
+  pub fn apply_percent(self, percent: Self, mode: RoundingMode) -> Result<Self> {
+    self.apply_fraction(percent, 100, mode)
+  }
+
+  /// Takes `basis_points` hundredths of a percent of `self`, rounded by `mode`: `-1250`
+  /// basis points of `200` is `-25`.
+  pub fn apply_basis_points(self, basis_points: Self, mode: RoundingMode) -> Result<Self> {
+    self.apply_fraction(basis_points, 10_000, mode)
+  }
+
+  /// `self / whole` in units of one `per`-th.
+  fn fraction_of(self, whole: Self, per: i128, mode: RoundingMode) -> Result<Self> {
+    if whole.0 == 0 {
+      return Err(Error::DivisionByZero {});
+    }
+    let numerator = self.0 as i128 * per * Self::SCALE as i128;
+    Self::checked(mode.divide(numerator, whole.0 as i128))
+  }
+
+  /// `rate` `per`-ths of `self`.
+  fn apply_fraction(self, rate: Self, per: i128, mode: RoundingMode) -> Result<Self> {
+    let divisor = per * Self::SCALE as i128;
+    Self::checked(mode.divide(self.0 as i128 * rate.0 as i128, divisor))
+  }
+
+  /// Parses like [`FromStr`], but accepts any number of fractional digits and rounds them
+  /// to `FRAC_DIGITS` by `mode`.
+  pub fn from_str_rounded(s: &str, mode: RoundingMode) -> Result<Self> {
+    Self::parse_with(s, ParseOptions::strict().rounding(Some(mode)))
+  }
+
+  /// Parses decimal text by the same rules as [`FromStr`], usable in const contexts.
+  pub const fn parse(s: &str) -> Result<Self> {
+    match parse(s, ParseOptions::strict(), true, FRAC_DIGITS, Self::MAX_UNITS as u64) {
+      Ok(parsed) if parsed.negative => Ok(Self(-(parsed.units as i64))),
+      Ok(parsed) => Ok(Self(parsed.units as i64)),
+      Err(error) => Err(Error::Parse(error)),
+    }
+  }
+
+  /// Parses decimal text accepting the forms enabled in `options`.
+  pub fn parse_with(s: &str, options: ParseOptions) -> Result<Self> {
+    let parsed = parse(s, options, true, FRAC_DIGITS, Self::MAX_UNITS as u64)?;
+    Self::checked(parsed.rounded(options))
+  }
+
+  /// Writes the shortest text of the value, as used by `Display`, into `buf`.
+  pub fn to_str_buf<'a>(&self, buf: &'a mut [u8; MAX_STR_LEN]) -> &'a str {
+    format_units(buf, self.is_negative(), self.0.unsigned_abs(), FRAC_DIGITS)
+  }
+
+  /// Displays in engineering notation, see [`Engineering`].
+  pub fn engineering(&self) -> Engineering {
+    Engineering::new(self.is_negative(), self.0.unsigned_abs(), FRAC_DIGITS)
+  }
+
+  /// Formats with digit grouping, a custom decimal mark or fraction digit limits, see
+  /// [`NumberFormat`].
+  pub fn format(&self) -> Formatted {
+    Formatted::new(self.is_negative(), self.0.unsigned_abs(), FRAC_DIGITS)
+  }
+
+  /// Parses text written in `format`, which may leave out the group separators.
+  pub fn parse_format(s: &str, format: NumberFormat) -> Result<Self> {
+    let parsed = parse_format(s, format, true, FRAC_DIGITS, Self::MAX_UNITS as u64)?;
+    Ok(Self(if parsed.negative { -(parsed.units as i64) } else { parsed.units as i64 }))
+  }
+
+  /// Converts to another digit configuration, with the same failure modes as
+  /// [`Decimal::convert`].
+  pub fn convert<const TO_INT_DIGITS: u32, const TO_FRAC_DIGITS: u32>(
+    self,
+  ) -> Result<SignedDecimal<TO_INT_DIGITS, TO_FRAC_DIGITS>> {
+    let magnitude = rescale(self.0.unsigned_abs() as u128, FRAC_DIGITS, TO_FRAC_DIGITS)? as i128;
+    SignedDecimal::checked(if self.is_negative() { -magnitude } else { magnitude })
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Neg for SignedDecimal<INT_DIGITS, FRAC_DIGITS> {
+  type Output = Self;
+
+  fn neg(self) -> Self::Output {
+    Self(-self.0)
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Add for SignedDecimal<INT_DIGITS, FRAC_DIGITS> {
+  type Output = Result<Self>;
+
+  fn add(self, rhs: Self) -> Self::Output {
+    Self::checked(self.0 as i128 + rhs.0 as i128)
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Sub for SignedDecimal<INT_DIGITS, FRAC_DIGITS> {
+  type Output = Result<Self>;
+
+  fn sub(self, rhs: Self) -> Self::Output {
+    Self::checked(self.0 as i128 - rhs.0 as i128)
+  }
+}
+
+/// Truncates the exact product towards zero, i.e. [`SignedDecimal::mul_rounded`] with
+/// [`RoundingMode::Down`].
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Mul for SignedDecimal<INT_DIGITS, FRAC_DIGITS> {
+  type Output = Result<Self>;
+
+  fn mul(self, rhs: Self) -> Self::Output {
+    self.mul_rounded(rhs, RoundingMode::Down)
+  }
+}
+
+/// Truncates the exact quotient towards zero, i.e. [`SignedDecimal::div_rounded`] with
+/// [`RoundingMode::Down`].
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Div for SignedDecimal<INT_DIGITS, FRAC_DIGITS> {
+  type Output = Result<Self>;
+
+  fn div(self, rhs: Self) -> Self::Output {
+    self.div_rounded(rhs, RoundingMode::Down)
+  }
+}
+
+/// Remainder of the truncated division, taking the sign of `self`, which is always exact.
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Rem for SignedDecimal<INT_DIGITS, FRAC_DIGITS> {
+  type Output = Result<Self>;
+
+  fn rem(self, rhs: Self) -> Self::Output {
+    if rhs.0 == 0 {
+      return Err(Error::DivisionByZero {});
+    }
+    Ok(Self(self.0 % rhs.0))
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> FromStr
+  for SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    Self::parse(s)
+  }
+}
+
+/// Formats like [`Decimal`], keeping the minus sign of negative values that round to zero,
+/// as std does for floats.
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> Display
+  for SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    fmt_units(f, self.is_negative(), self.0.unsigned_abs(), FRAC_DIGITS)
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> LowerExp
+  for SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    fmt_exp(
+      f,
+      self.is_negative(),
+      self.0.unsigned_abs(),
+      FRAC_DIGITS,
+      Notation::Scientific { upper: false },
+    )
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> UpperExp
+  for SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    fmt_exp(
+      f,
+      self.is_negative(),
+      self.0.unsigned_abs(),
+      FRAC_DIGITS,
+      Notation::Scientific { upper: true },
+    )
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> From<Decimal<INT_DIGITS, FRAC_DIGITS>>
+  for SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+  fn from(value: Decimal<INT_DIGITS, FRAC_DIGITS>) -> Self {
+    Self(value.0 as i64)
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> TryFrom<SignedDecimal<INT_DIGITS, FRAC_DIGITS>>
+  for Decimal<INT_DIGITS, FRAC_DIGITS>
+{
+  type Error = Error;
+
+  fn try_from(value: SignedDecimal<INT_DIGITS, FRAC_DIGITS>) -> Result<Self> {
+    if value.is_negative() {
+      return Err(Error::Negative {});
+    }
+    Ok(value.unsigned_abs())
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> From<SignedDecimal<INT_DIGITS, FRAC_DIGITS>>
+  for f64
+{
+  fn from(value: SignedDecimal<INT_DIGITS, FRAC_DIGITS>) -> Self {
+    value.to_f64()
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> TryFrom<f64>
+  for SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+  type Error = Error;
+
+  fn try_from(value: f64) -> Result<Self> {
+    let magnitude = Self::from(Decimal::try_from(value.abs())?);
+    Ok(if value < 0.0 { -magnitude } else { magnitude })
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> TryFrom<i32>
+  for SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+  type Error = Error;
+
+  fn try_from(value: i32) -> Result<Self> {
+    Self::try_from(value as i128)
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> TryFrom<i64>
+  for SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+  type Error = Error;
+
+  fn try_from(value: i64) -> Result<Self> {
+    Self::try_from(value as i128)
+  }
+}
+
+impl<const INT_DIGITS: u32, const FRAC_DIGITS: u32> TryFrom<i128>
+  for SignedDecimal<INT_DIGITS, FRAC_DIGITS>
+{
+  type Error = Error;
+
+  fn try_from(value: i128) -> Result<Self> {
+    let magnitude = Decimal::try_from(value.unsigned_abs())?;
+    Ok(if value < 0 { -Self::from(magnitude) } else { Self::from(magnitude) })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::decimal::SafeDecimal;
+
+  #[test]
+  fn test_new_negative() {
+    let decimal = SignedSafeDecimal::new(true, 123, 456789).unwrap();
+    assert!(decimal.is_negative());
+    assert_eq!(decimal.integral(), 123);
+    assert_eq!(decimal.fractional(), 456789);
+  }
+
+  #[test]
+  fn test_new_overflow() {
+    assert!(matches!(SignedSafeDecimal::new(true, 1_000_000_000, 0), Err(Error::Overflow {})));
+    assert!(matches!(SignedSafeDecimal::new(false, 0, 1_000_000), Err(Error::Overflow {})));
+  }
+
+  #[test]
+  fn test_parts_past_u32() {
+    let decimal = SignedDecimal::<12, 3>::try_from_parts(true, 999_999_999_999, 5).unwrap();
+    assert_eq!(decimal.to_string(), "-999999999999.005");
+    assert_eq!(decimal.to_parts(), (999_999_999_999, 5));
+    assert_eq!(SignedDecimal::<12, 3>::new(true, u32::MAX, 0).unwrap().to_parts().0, 4_294_967_295);
+  }
+
+  #[test]
+  fn test_constants() {
+    const REBATE: SignedSafeDecimal = SignedSafeDecimal::from_parts(true, 0, 250_000);
+    const TICK: SignedSafeDecimal = SignedSafeDecimal::from_units(-10_000);
+    assert_eq!(REBATE.to_string(), "-0.25");
+    assert_eq!(TICK.to_string(), "-0.01");
+    assert_eq!(SignedSafeDecimal::ZERO.to_string(), "0");
+    assert_eq!(SignedSafeDecimal::ONE.to_string(), "1");
+    assert_eq!(SignedSafeDecimal::MAX.to_string(), "999999999.999999");
+    assert_eq!(SignedSafeDecimal::MIN.to_string(), "-999999999.999999");
+    assert_eq!(SignedSafeDecimal::MIN_POSITIVE.to_string(), "0.000001");
+    assert_eq!(SignedSafeDecimal::SCALE, 1_000_000);
+    assert_eq!(SignedSafeDecimal::DECIMALS, 6);
+    assert_eq!(TICK.to_units(), -10_000);
+    assert!(matches!(
+      SignedSafeDecimal::try_from_units(-1_000_000_000_000_000),
+      Err(Error::Overflow {})
+    ));
+  }
+
+  #[test]
+  fn test_sub_below_zero() {
+    let a = SignedSafeDecimal::from_str("2.1").unwrap();
+    let b = SignedSafeDecimal::from_str("5.3").unwrap();
+    assert_eq!((a - b).unwrap().to_string(), "-3.2");
+  }
+
+  #[test]
+  fn test_add_overflow() {
+    let a = SignedSafeDecimal::from_str("-999999999.999999").unwrap();
+    let b = SignedSafeDecimal::from_str("-0.000001").unwrap();
+    assert!(matches!(a + b, Err(Error::Overflow {})));
+  }
+
+  #[test]
+  fn test_mul_div_signs() {
+    let a = SignedSafeDecimal::from_str("-2.5").unwrap();
+    let b = SignedSafeDecimal::from_str("3").unwrap();
+    assert_eq!((a * b).unwrap().to_string(), "-7.5");
+    assert_eq!((a * -b).unwrap().to_string(), "7.5");
+    assert_eq!((b / a).unwrap().to_string(), "-1.2");
+  }
+
+  #[test]
+  fn test_abs_signum() {
+    let a = SignedSafeDecimal::from_str("-1.25").unwrap();
+    assert_eq!(a.abs().to_string(), "1.25");
+    assert_eq!(a.unsigned_abs().to_string(), "1.25");
+    assert_eq!(a.signum(), -1);
+    assert_eq!((-a).signum(), 1);
+    assert_eq!(SignedSafeDecimal::from_str("0").unwrap().signum(), 0);
+    assert_eq!(SignedDecimal::<0, 6>::from_units(-5).signum(), -1);
+    assert_eq!(SignedDecimal::<0, 6>::MAX.signum(), 1);
+  }
+
+  #[test]
+  fn test_from_str_display() {
+    assert_eq!(SignedSafeDecimal::from_str("-0.5").unwrap().to_string(), "-0.5");
+    assert_eq!(SignedSafeDecimal::from_str("-0").unwrap().to_string(), "0");
+    assert_eq!(SignedSafeDecimal::from_str("12.34").unwrap().to_string(), "12.34");
+    assert!(SignedSafeDecimal::from_str("--1").is_err());
+  }
+
+  #[test]
+  fn test_unsigned_conversions() {
+    let unsigned = SafeDecimal::new(7, 250000).unwrap();
+    let signed = SignedSafeDecimal::from(unsigned);
+    assert_eq!(SafeDecimal::try_from(signed).unwrap(), unsigned);
+    assert!(matches!(SafeDecimal::try_from(-signed), Err(Error::Negative {})));
+  }
+
+  #[test]
+  fn test_try_from_i64() {
+    let decimal: SignedSafeDecimal = (-42i64).try_into().unwrap();
+    assert_eq!(decimal.to_string(), "-42");
+    assert!(SignedSafeDecimal::try_from(i64::MIN).is_err());
+  }
+
+  #[test]
+  fn test_serialize() {
+    let decimal = SignedSafeDecimal::from_str("-123.45").unwrap();
+    assert_eq!(serde_json::to_value(decimal).unwrap(), serde_json::json!("-123.45"));
+    let json = crate::serde::as_json_number::serialize(&decimal, serde_json::value::Serializer);
+    assert_eq!(json.unwrap(), serde_json::json!(-123.45));
+    let result: SignedSafeDecimal = serde_json::from_value(serde_json::json!("-123.45")).unwrap();
+    assert_eq!(result, decimal);
+    let json = serde_json::to_string(&decimal).unwrap();
+    assert_eq!(serde_json::from_str::<SignedSafeDecimal>(&json).unwrap(), decimal);
+  }
+
+  #[test]
+  fn test_convert() {
+    let decimal = SignedSafeDecimal::from_str("-1234.5").unwrap();
+    assert_eq!(decimal.convert::<12, 3>().unwrap().to_string(), "-1234.5");
+    assert!(matches!(decimal.convert::<3, 12>(), Err(Error::Overflow {})));
+    let precise = SignedSafeDecimal::from_str("-1.0005").unwrap();
+    assert!(matches!(precise.convert::<12, 3>(), Err(Error::PrecisionLoss {})));
+  }
+
+  #[test]
+  fn test_f64_conversions() {
+    let decimal = SignedSafeDecimal::from_str("-123.45").unwrap();
+    assert_eq!(decimal.to_f64(), -123.45);
+    assert_eq!(SignedSafeDecimal::try_from(-123.45).unwrap(), decimal);
+    assert!(matches!(SignedSafeDecimal::try_from(-1e9), Err(Error::Overflow {})));
+    assert!(matches!(SignedSafeDecimal::try_from(f64::NEG_INFINITY), Err(Error::NotFinite {})));
+    assert!(matches!(SignedSafeDecimal::try_from(-0.1234567), Err(Error::PrecisionLoss {})));
+  }
+
+  #[test]
+  fn test_rounded_signs() {
+    let a = SignedSafeDecimal::from_str("-0.000003").unwrap();
+    let half = SignedSafeDecimal::from_str("0.5").unwrap();
+    assert_eq!((a * half).unwrap().to_string(), "-0.000001");
+    assert_eq!(a.mul_rounded(half, RoundingMode::Floor).unwrap().to_string(), "-0.000002");
+    assert_eq!(a.mul_rounded(half, RoundingMode::Ceiling).unwrap().to_string(), "-0.000001");
+    assert_eq!(a.mul_rounded(half, RoundingMode::HalfUp).unwrap().to_string(), "-0.000002");
+
+    let one = SignedSafeDecimal::from_str("-1").unwrap();
+    let three = SignedSafeDecimal::from_str("3").unwrap();
+    assert_eq!(one.div_rounded(three, RoundingMode::Floor).unwrap().to_string(), "-0.333334");
+
+    let parse = |s, mode| SignedSafeDecimal::from_str_rounded(s, mode).unwrap().to_string();
+    assert_eq!(parse("-2.0000005", RoundingMode::HalfEven), "-2");
+    assert_eq!(parse("-2.0000005", RoundingMode::HalfUp), "-2.000001");
+    assert_eq!(parse("-2.0000001", RoundingMode::Ceiling), "-2");
+    assert_eq!(parse("-2.0000001", RoundingMode::Floor), "-2.000001");
+    assert_eq!(parse("2.0000001", RoundingMode::Ceiling), "2.000001");
+  }
+
+  #[test]
+  fn test_fused_mul_div_signs() {
+    let a = SignedSafeDecimal::from_str("-1000").unwrap();
+    let b = SignedSafeDecimal::from_str("17").unwrap();
+    let c = SignedSafeDecimal::from_str("30").unwrap();
+    assert_eq!(a.mul_div(b, c, RoundingMode::HalfEven).unwrap().to_string(), "-566.666667");
+    assert_eq!(a.mul_div(b, c, RoundingMode::Ceiling).unwrap().to_string(), "-566.666666");
+    assert_eq!(a.mul_div(b, -c, RoundingMode::Floor).unwrap().to_string(), "566.666666");
+    assert_eq!(
+      SignedSafeDecimal::MIN
+        .mul_div(SignedSafeDecimal::MIN, SignedSafeDecimal::MAX, RoundingMode::Down)
+        .unwrap(),
+      SignedSafeDecimal::MAX
+    );
+
+    let part = SignedSafeDecimal::from_str("-25").unwrap();
+    let whole = SignedSafeDecimal::from_str("200").unwrap();
+    assert_eq!(part.as_percent_of(whole, RoundingMode::HalfEven).unwrap().to_string(), "-12.5");
+    assert_eq!(
+      part.as_basis_points_of(-whole, RoundingMode::HalfEven).unwrap().to_string(),
+      "1250"
+    );
+    let rate = SignedSafeDecimal::from_str("-0.5").unwrap();
+    let tiny = SignedSafeDecimal::from_str("0.000300").unwrap();
+    assert_eq!(tiny.apply_percent(rate, RoundingMode::HalfEven).unwrap().to_string(), "-0.000002");
+    assert_eq!(tiny.apply_percent(rate, RoundingMode::Floor).unwrap().to_string(), "-0.000002");
+    assert_eq!(tiny.apply_percent(rate, RoundingMode::Ceiling).unwrap().to_string(), "-0.000001");
+    let bps = SignedSafeDecimal::from_str("-50").unwrap();
+    assert_eq!(
+      tiny.apply_basis_points(bps, RoundingMode::HalfEven).unwrap().to_string(),
+      "-0.000002"
+    );
+    assert_eq!(
+      tiny.apply_basis_points(bps, RoundingMode::Ceiling).unwrap().to_string(),
+      "-0.000001"
+    );
+    assert_eq!(whole.apply_basis_points(-bps, RoundingMode::Down).unwrap().to_string(), "1");
+  }
+
+  #[test]
+  fn test_div_rem_by_zero() {
+    let a = SignedSafeDecimal::from_str("-10").unwrap();
+    let zero = SignedSafeDecimal::from_str("0").unwrap();
+    assert!(matches!(a / zero, Err(Error::DivisionByZero {})));
+    assert!(matches!(a.div_rounded(zero, RoundingMode::Up), Err(Error::DivisionByZero {})));
+    assert!(matches!(a % zero, Err(Error::DivisionByZero {})));
+  }
+
+  #[test]
+  fn test_rem_sign() {
+    let a = SignedSafeDecimal::from_str("-10.5").unwrap();
+    let b = SignedSafeDecimal::from_str("3.2").unwrap();
+    assert_eq!((a % b).unwrap().to_string(), "-0.9");
+    assert_eq!((-a % b).unwrap().to_string(), "0.9");
+    assert_eq!((a % -b).unwrap().to_string(), "-0.9");
+  }
+}
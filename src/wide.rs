@@ -0,0 +1,97 @@
+//! Multiplication followed by division through a 256-bit intermediate.
+
+use crate::rounding::RoundingMode;
+
+/// `a * b / c` for a non-zero `c`, rounded by `mode` as a quotient of sign `negative`, or
+/// `None` when it does not fit in a `u128`.
+pub(crate) fn mul_div(
+  a: u128,
+  b: u128,
+  c: u128,
+  mode: RoundingMode,
+  negative: bool,
+) -> Option<u128> {
+  let (quotient, remainder) = mul_div_rem(a, b, c)?;
+  if remainder != 0 && mode.away_from_zero(quotient % 2 != 0, remainder, c, negative) {
+    quotient.checked_add(1)
+  } else {
+    Some(quotient)
+  }
+}
+
+/// Truncated quotient and remainder of `a * b / c` for a non-zero `c`, or `None` when the
+/// quotient does not fit in a `u128`.
+fn mul_div_rem(a: u128, b: u128, c: u128) -> Option<(u128, u128)> {
+  let (high, low) = widening_mul(a, b);
+  if high == 0 {
+    return Some((low / c, low % c));
+  }
+  if high >= c {
+    return None;
+  }
+  // Binary long division of the low half, with the running remainder always below `c`.
+  let mut remainder = high;
+  let mut quotient = 0;
+  for bit in (0..128).rev() {
+    let carry = remainder >> 127;
+    remainder = remainder << 1 | (low >> bit) & 1;
+    quotient <<= 1;
+    if carry != 0 || remainder >= c {
+      remainder = remainder.wrapping_sub(c);
+      quotient |= 1;
+    }
+  }
+  Some((quotient, remainder))
+}
+
+/// Full 256-bit product as its high and low halves.
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+  const MASK: u128 = u64::MAX as u128;
+  let (a_high, a_low) = (a >> 64, a & MASK);
+  let (b_high, b_low) = (b >> 64, b & MASK);
+  let low = a_low * b_low;
+  let cross = a_low * b_high;
+  let (middle, middle_carry) = cross.overflowing_add(a_high * b_low);
+  let (low, low_carry) = low.overflowing_add(middle << 64);
+  let high = a_high * b_high + (middle >> 64) + ((middle_carry as u128) << 64) + low_carry as u128;
+  (high, low)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_widening_mul() {
+    assert_eq!(widening_mul(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+    assert_eq!(widening_mul(1 << 64, 1 << 64), (1, 0));
+    assert_eq!(widening_mul(u64::MAX as u128, u64::MAX as u128), (0, (u64::MAX as u128).pow(2)));
+    assert_eq!(widening_mul(3 << 126, 2), (1, 1 << 127));
+  }
+
+  #[test]
+  fn test_mul_div_rem() {
+    assert_eq!(mul_div_rem(7, 11, 4), Some((19, 1)));
+    assert_eq!(mul_div_rem(u128::MAX, u128::MAX, u128::MAX), Some((u128::MAX, 0)));
+    assert_eq!(mul_div_rem(u128::MAX, 10, 20), Some((u128::MAX / 2, 10)));
+    assert_eq!(mul_div_rem(u128::MAX, 3, 2), None);
+    // 2^200 / (2^80 + 1) is just above 2^120 - 2^40.
+    let quotient = (1 << 120) - (1 << 40);
+    assert_eq!(mul_div_rem(1 << 100, 1 << 100, (1 << 80) + 1).map(|(q, _)| q), Some(quotient));
+    for (a, b, c) in [(u64::MAX as u128, 1_000_003, 999_983), (12_345, 67_890, 7)] {
+      assert_eq!(mul_div_rem(a, b, c), Some((a * b / c, a * b % c)));
+    }
+  }
+
+  #[test]
+  fn test_mul_div() {
+    assert_eq!(mul_div(5, 1, 2, RoundingMode::HalfEven, false), Some(2));
+    assert_eq!(mul_div(7, 1, 2, RoundingMode::HalfEven, false), Some(4));
+    assert_eq!(mul_div(5, 1, 2, RoundingMode::Floor, true), Some(3));
+    assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX, RoundingMode::Up, false), Some(u128::MAX));
+    assert_eq!(mul_div(u128::MAX, 3, 3, RoundingMode::Up, false), Some(u128::MAX));
+    assert_eq!(mul_div(u128::MAX, 4, 3, RoundingMode::Up, false), None);
+    assert_eq!(mul_div(u128::MAX, 1, u128::MAX - 1, RoundingMode::Down, false), Some(1));
+    assert_eq!(mul_div(u128::MAX, 1, u128::MAX - 1, RoundingMode::Up, false), Some(2));
+  }
+}